@@ -0,0 +1,441 @@
+use std::{error::Error, fs, process};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::prelude::*;
+use std::path::Path;
+use std::time::SystemTime;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use regex::Regex;
+//
+// Local files.
+//
+use common_functions::*;
+use common_functions::arguments::*;
+use common_functions::cached::*;
+use common_functions::malfunction::*;
+use common_functions::manifest::*;
+use common_functions::nationalities::*;
+use common_functions::overrides::*;
+use common_functions::text_field::*;
+use common_functions::utils::*;
+
+pub const HEADER_LENGTH: usize =	1024;	// The generator's header comment is well within the first 1K of a counter.
+
+const DATA_DIRECTORY: &str =		"./data/";
+//
+// The generator each data file is run through (see run_nationality.sh and run_miscellaneous.sh), it decides which rows make counters
+// and where they go.
+//
+#[derive(Clone, Copy)]
+enum DataKind {
+	Ordnance,
+	Vehicles,
+	SingleMan,
+	MultiMan,
+	SupportWeapons,
+	LandingCraft,
+	Aircraft,
+	Miscellaneous,
+}
+//
+// "<group>_<name>.csv" for each nationality group of NATIONALITIES_FILE, then the shared ones, in the order run_all.sh runs them (a
+// counter written twice is the last row's).
+//
+const GROUP_DATA_FILES: [(&str, DataKind); 5] = [
+	("ordnance", DataKind::Ordnance),
+	("vehicles", DataKind::Vehicles),
+	("smc", DataKind::SingleMan),
+	("mmc", DataKind::MultiMan),
+	("sw", DataKind::SupportWeapons),
+];
+
+const SHARED_DATA_FILES: [(&str, DataKind); 4] = [
+	("landing_craft_and_boats", DataKind::LandingCraft),
+	("common_vehicles", DataKind::Vehicles),
+	("aircraft", DataKind::Aircraft),
+	("miscellaneous", DataKind::Miscellaneous),
+];
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct CheckArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[arg(long)]
+	prune: bool,		// Delete the extra counters, only those starting with our generator header.
+}
+//
+// A counter the data files make, with the row it's made from.
+//
+struct ExpectedOutput {
+	source: String,
+	line: u64,
+	row: String,			// The row's fingerprint (see row_fingerprint()).
+	cached: Option<String>,	// The pre-made counter of a row with the "copy" override.
+}
+
+#[derive(Default)]
+struct Report {
+	missing: Vec<String>,
+	stale: Vec<String>,
+	extra: Vec<String>,
+	unknown: Vec<String>,	// Extra files without our header, never pruned.
+	pruned: Vec<String>,
+	unlisted: Vec<String>,	// Differences with the manifest, only reported.
+}
+
+fn list_svg_files(directory: &String) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+
+	if let Ok(entries) = fs::read_dir(directory) {
+		for entry in entries.flatten() {
+			let name: String = entry.file_name().to_string_lossy().to_string();
+
+			if entry.path().is_file() && name.ends_with(".svg") {
+				result.push(name);
+			}
+		}
+	}
+
+	result.sort();
+
+	return result;
+}
+//
+// Maps "<nationality>/[<category>/]<piece>.svg" (relative to the destination) to its source in the cached tree.
+//
+fn cached_copy_outputs() -> BTreeMap<String, String> {
+	let mut result: BTreeMap<String, String> = Default::default();
+	let mut directories: Vec<String> = Default::default();
+
+	if let Ok(entries) = fs::read_dir(CACHED_DIRECTORY) {
+		for entry in entries.flatten() {
+			let nationality: String = entry.file_name().to_string_lossy().to_string();
+
+			if !entry.path().is_dir() {
+				continue;
+			}
+
+			directories.push(format!("{nationality}/"));
+
+			if let Ok(categories) = fs::read_dir(entry.path()) {
+				for category in categories.flatten() {
+					let name: String = category.file_name().to_string_lossy().to_string();
+
+					if category.path().is_dir() && CACHED_COPY_DIRECTORY != name {
+						directories.push(format!("{nationality}/{name}/"));
+					}
+				}
+			}
+		}
+	}
+
+	for directory in directories {
+		for name in list_svg_files(&format!("{CACHED_DIRECTORY}{directory}{CACHED_COPY_DIRECTORY}/")) {
+			result.insert(format!("{directory}{name}"), format!("{CACHED_DIRECTORY}{directory}{CACHED_COPY_DIRECTORY}/{name}"));
+		}
+	}
+
+	return result;
+}
+
+fn data_files() -> Vec<(String, DataKind)> {
+	let groups: BTreeSet<String> = read_nationalities().iter().filter(|nationality| !nationality.group.is_empty()).map(|nationality| nationality.group.to_string()).collect();
+	let mut result: Vec<(String, DataKind)> = Default::default();
+
+	for group in &groups {
+		for (name, kind) in GROUP_DATA_FILES {
+			result.push((format!("{DATA_DIRECTORY}{group}_{name}.csv"), kind));
+		}
+	}
+
+	for (name, kind) in SHARED_DATA_FILES {
+		result.push((format!("{DATA_DIRECTORY}{name}.csv"), kind));
+	}
+
+	return result.into_iter().filter(|(path, _)| Path::new(path).is_file()).collect();
+}
+//
+// "piece|piece@xx" gives the pieces of the row, each with its nationality ("xx" or the row's).
+//
+fn row_pieces(pieces: &str, nationality: &String) -> Vec<(String, String)> {
+	return extract_vector(&pieces.to_string(), OVERRIDE_DELIMITER).iter().map(|piece| {
+		if piece.contains(NATIONALITY_DELIMITER) { split_alternate_nationality(piece) } else { (piece.to_string(), nationality.to_string()) }
+	}).collect();
+}
+//
+// The counters a row makes, relative to the destination, and whether they're copied: the same rows are skipped as by the generators,
+// guns get their malfunctioned back (and limbered side) and SW, SMC and vehicle rows with the "copy" override are copied.
+//
+fn row_outputs(kind: DataKind, column: &dyn Fn(&str) -> String) -> Vec<(String, bool)> {
+	let overrides_text: String = column("overrides");
+	let mut overrides: Overrides = Default::default();
+	let mut result: Vec<(String, bool)> = Default::default();
+
+	match kind {
+		DataKind::Ordnance | DataKind::Vehicles | DataKind::LandingCraft => {
+			if NOVR_ANNOUNCE == overrides_text || overrides_text.contains(NOVR_IGNORE) {
+				return result;
+			}
+		},
+		DataKind::Aircraft => {
+			if overrides_text.contains(NOVR_IGNORE) {
+				return result;
+			}
+		},
+		DataKind::SingleMan => {
+			if column("nationality").is_empty() || overrides_text.contains(NOVR_IGNORE) {
+				return result;
+			}
+		},
+		DataKind::MultiMan | DataKind::SupportWeapons => {
+			if column("nationality").is_empty() {
+				return result;
+			}
+		},
+		DataKind::Miscellaneous => {
+			if column("folder").is_empty() {
+				return result;
+			}
+		},
+	}
+
+	overrides.sanitize(&overrides_text);
+
+	match kind {
+		DataKind::Ordnance => {
+			let piece: String = column("piece");
+			let nationality: String = if overrides_text.contains(NOVR_NATIONALITY) { extract_from(&overrides_text, NOVR_NATIONALITY) } else { nationality_from_piece(&piece) };
+			let limbered: String = column("limbered");
+
+			for (piece, nationality) in row_pieces(&piece, &nationality) {
+				let folder: String = nationality_folder(&nationality, "gun");
+				let mut fronts: Vec<String> = vec![ piece.to_string() ];
+
+				if !limbered.is_empty() {
+					let limbered_piece: String = if limbered.contains(LIMBERED_PIECE) { extract_from(&limbered, LIMBERED_PIECE) } else { LIMBERED_PIECE_DEFAULT.to_string() };
+
+					fronts.push(convert_text(&limbered_piece, COPY_FIELD, &piece));
+				}
+
+				for front in fronts {
+					result.push((format!("{folder}{front}.svg"), false));
+					result.push((format!("{folder}{front}b.svg"), false));
+				}
+			}
+		},
+		DataKind::Vehicles => {
+			for (piece, nationality) in row_pieces(&column("piece"), &extract_from(&overrides_text, NOVR_NATIONALITY)) {
+				if !nationality.is_empty() {
+					result.push((format!("{0}{piece}.svg", nationality_folder(&nationality, "veh")), overrides.copy));
+				}
+			}
+		},
+		DataKind::LandingCraft => {
+			for (piece, nationality) in row_pieces(&column("piece"), &extract_from(&overrides_text, NOVR_NATIONALITY)) {
+				if !nationality.is_empty() {
+					result.push((format!("{0}{piece}.svg", nationality_folder(SHARED_NATIONALITY, "veh")), false));
+				}
+			}
+		},
+		DataKind::Aircraft => {
+			if !extract_from(&overrides_text, NOVR_NATIONALITY).is_empty() {
+				result.push((format!("{0}{1}.svg", nationality_folder(SHARED_NATIONALITY, "veh"), column("piece")), false));
+			}
+		},
+		DataKind::SingleMan | DataKind::MultiMan => {
+			for (piece, nationality) in row_pieces(&column("piece"), &column("nationality")) {
+				result.push((format!("{0}{piece}.svg", nationality_folder(&nationality, "inf")), overrides.copy && matches!(kind, DataKind::SingleMan)));
+			}
+		},
+		DataKind::SupportWeapons => {
+			for (piece, nationality) in row_pieces(&column("piece"), &column("nationality")) {
+				let folder: String = nationality_folder(&nationality, "sw");
+
+				if overrides.copy || "Ignore" != column("counter") {
+					result.push((format!("{folder}{piece}.svg"), overrides.copy));
+				}
+				//
+				// A machine gun, light mortar or ATR front with a "repair" column also generates its malfunctioned back.
+				//
+				let (repair, _, repair_text, _) = split_sw_repair(&column("repair"));
+
+				if !overrides.copy && !column("counter").starts_with('_') && !sw_repair_back_token(&column("counter"), &column("value"), repair, &repair_text).is_empty() {
+					result.push((format!("{folder}{piece}{BACK_PIECE_SUFFIX}.svg"), false));
+				}
+			}
+		},
+		DataKind::Miscellaneous => {
+			if overrides.copy {
+				result.push((format!("{0}{1}.svg", nationality_folder(&column("folder"), ""), column("piece")), true));
+			}
+		},
+	}
+
+	return result;
+}
+//
+// Every counter of the data files, by output path.
+//
+fn expected_outputs() -> Result<BTreeMap<String, ExpectedOutput>, Box<dyn Error>> {
+	let mut result: BTreeMap<String, ExpectedOutput> = Default::default();
+
+	for (path, kind) in data_files() {
+		let mut rdr = csv::Reader::from_path(&path).map_err(|why| format!("couldn't open '{path}': {why}"))?;
+		let headers = rdr.headers()?.clone();
+
+		for row in rdr.records() {
+			let row = row?;
+			let column = |name: &str| -> String { headers.iter().position(|header| name == header).and_then(|index| row.get(index)).unwrap_or_default().to_string() };
+
+			for (output, copied) in row_outputs(kind, &column) {
+				let cached: Option<String> = output.rsplit_once('/').filter(|_| copied).map(|(folder, name)| format!("{CACHED_DIRECTORY}{folder}/{CACHED_COPY_DIRECTORY}/{name}"));
+
+				result.insert(output, ExpectedOutput {
+					source: path.trim_start_matches("./").to_string(),
+					line: row.position().map(|position| position.line()).unwrap_or(0),
+					row: row_fingerprint(&row),
+					cached,
+				});
+			}
+		}
+	}
+
+	return Ok(result);
+}
+
+fn modified(path: &String) -> Option<SystemTime> {
+	return fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+}
+//
+// Only files starting with the comment written by generate_counter_header_svg_elements() are ours to delete.
+//
+fn has_generator_header(path: &String) -> bool {
+	let mut buffer: Vec<u8> = vec![0; HEADER_LENGTH];
+	let length: usize = fs::File::open(path).and_then(|mut file| file.read(&mut buffer)).unwrap_or_default();
+	let header: String = String::from_utf8_lossy(&buffer[..length]).to_string();
+
+	return header.starts_with("<?xml") && header.contains(&format!("Tester(s): {TESTERS}"));
+}
+//
+// The fingerprint of the row the counter was generated from, in its metadata.
+//
+fn generated_row(path: &String) -> Option<String> {
+	let text: String = fs::read_to_string(path).unwrap_or_default();
+
+	return Regex::new(r#""source_row":"([0-9a-f]+)""#).unwrap().captures(&text).map(|found| found[1].to_string());
+}
+//
+// A generated counter is stale when its row has changed since, a copied one when the cached copy is newer.
+//
+fn stale_reason(path: &String, expected: &ExpectedOutput) -> Option<String> {
+	let Some(cached) = &expected.cached else {
+		return match generated_row(path) {
+			None => Some(format!("no row fingerprint, {0} line {1}", expected.source, expected.line)),
+			Some(row) if row != expected.row => Some(format!("{0} line {1} has changed since it was generated", expected.source, expected.line)),
+			Some(_) => None,
+		};
+	};
+
+	let output_time: Option<SystemTime> = modified(path);
+
+	return modified(cached).filter(|cached_time| output_time.is_some_and(|output_time| *cached_time > output_time)).map(|_| format!("older than {cached}"));
+}
+
+fn check_outputs(args: &CheckArguments) -> Result<Report, Box<dyn Error>> {
+	let mut result: Report = Default::default();
+	let destination: &String = &args.outputs.destination;
+	let expected: BTreeMap<String, ExpectedOutput> = expected_outputs()?;
+	let copies: BTreeMap<String, String> = cached_copy_outputs();
+	let mut directories: BTreeSet<String> = Default::default();
+	//
+	// Missing and stale outputs.
+	//
+	for (output, row) in &expected {
+		let path: String = format!("{destination}{output}");
+
+		directories.insert(match output.rfind('/') { Some(index) => output[..=index].to_string(), None => "".to_string() });
+
+		if !Path::new(&path).is_file() {
+			result.missing.push(format!("{output} ({0} line {1})", row.source, row.line));
+		} else if let Some(reason) = stale_reason(&path, row) {
+			result.stale.push(format!("{output} ({reason})"));
+		}
+	}
+	//
+	// Extra outputs, only the directories the generators write to are checked (not the depiction "svg" folders).
+	//
+	for directory in &directories {
+		for name in list_svg_files(&format!("{destination}{directory}")) {
+			let output: String = format!("{directory}{name}");
+
+			if expected.contains_key(&output) || copies.contains_key(&output) {
+				continue;
+			}
+
+			if !has_generator_header(&format!("{destination}{output}")) {
+				result.unknown.push(output);
+			} else if args.prune {
+				match fs::remove_file(format!("{destination}{output}")) {
+					Err(why) => panic!("check_outputs()@{0}: couldn't remove '{1}': {2}", line!(), output, why),
+					Ok(_) => result.pruned.push(output),
+				}
+			} else {
+				result.extra.push(output);
+			}
+		}
+	}
+	//
+	// The manifest of the last run should list the same counters.
+	//
+	let manifest: String = manifest_file(destination, &args.outputs.manifest);
+	let entries: Vec<ManifestEntry> = read_manifest(&manifest);
+
+	if !entries.is_empty() {
+		let listed: BTreeSet<&String> = entries.iter().map(|entry| &entry.output).collect();
+
+		for entry in entries.iter().filter(|entry| !expected.contains_key(&entry.output)) {
+			result.unlisted.push(format!("{0} is in '{manifest}' ({1} line {2}) but no row makes it", entry.output, entry.source, entry.line));
+		}
+
+		for output in expected.keys().filter(|output| !listed.contains(output)) {
+			result.unlisted.push(format!("{output} isn't in '{manifest}'"));
+		}
+	}
+
+	return Ok(result);
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = CheckArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let report: Report = check_outputs(&args)?;
+
+	for (label, outputs) in [("Missing", &report.missing), ("Stale", &report.stale), ("Extra", &report.extra), ("Unknown", &report.unknown), ("Pruned", &report.pruned), ("Manifest", &report.unlisted)] {
+		for output in outputs {
+			println!("{label}: {output}");
+		}
+	}
+
+	if !args.outputs.quiet {
+		println!("{0} missing, {1} stale, {2} extra, {3} unknown, {4} pruned, {5} manifest difference(s)", report.missing.len(), report.stale.len(), report.extra.len(), report.unknown.len(), report.pruned.len(), report.unlisted.len());
+	}
+
+	if !report.missing.is_empty() || !report.stale.is_empty() || !report.extra.is_empty() {
+		return Err("Destination tree is out of date".into());
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
@@ -1,6 +1,6 @@
-use clap::Parser;
+use clap::{Args, Parser};
 //
-// Sanitized and parsed program arguments.
+// Sanitized and parsed program arguments of the generators.
 //
 #[derive(Parser, Debug, Default)]
 #[command(version, about, long_about = None)]
@@ -16,6 +16,34 @@ pub struct Arguments {
 	
 	#[arg(long)]
 	pub quiet: bool,
+
+	#[arg(long, default_value = "")]
+	pub source: String,		// CSV file name recorded in the manifest (the data is still read from stdin).
+
+	#[arg(long, default_value = "")]
+	pub manifest: String,	// Defaults to MANIFEST_FILE_NAME in the destination.
+
+	#[arg(long, default_value = "")]
+	pub palette: String,	// Palette from PALETTES_FILE (e.g., "colorblind"), the default colors when empty.
+
+	#[arg(long, default_value = "")]
+	pub profile: String,	// Render profile, "bevel" or "flat" (see profiles.rs), the bevel when empty.
+
+	#[arg(long)]
+	pub inline: bool,		// Replace the image references with their contents (see inline.rs).
+
+	#[arg(long, default_value = "")]
+	pub missing: String,	// What to do about missing depictions, "placeholder" or "strict" (see depictions.rs).
+
+	#[arg(long)]
+	pub images: Vec<String>,	// More folders to look for depictions in, after the cached ones (repeat for each folder).
+
+	#[arg(long)]
+	pub optimize: bool,		// Smaller counters: no comments, trimmed numbers, fewer nested <svg> (see optimize.rs).
+
+	#[arg(long)]
+	pub shared_defs: bool,	// "--optimize" and refer to SHARED_DEFS_FILE instead of repeating the bevel filters in each counter
+							// (renderers that don't follow url() references to other files, resvg and most browsers, drop the bevel).
 }
 
 impl Clone for Arguments {
@@ -24,7 +52,16 @@ impl Clone for Arguments {
 			destination: String::from(&self.destination),
 			debug: self.debug,
 			notes: self.notes,
-			quiet: self.quiet
+			quiet: self.quiet,
+			source: String::from(&self.source),
+			manifest: String::from(&self.manifest),
+			palette: String::from(&self.palette),
+			profile: String::from(&self.profile),
+			inline: self.inline,
+			missing: String::from(&self.missing),
+			images: self.images.clone(),
+			optimize: self.optimize,
+			shared_defs: self.shared_defs,
 		}  
 	}
 }
@@ -34,3 +71,35 @@ impl Arguments {
 		self.destination.push_str("/"); // Make sure there's a trailing '/' --- lazy (TODO: for now)
 	}
 }
+//
+// The arguments of the tools that only look at the counters already written (e.g., check_counter_outputs), flattened into their own
+// so that their help doesn't list the generators' options.
+//
+#[derive(Args, Debug, Default, Clone)]
+pub struct OutputArguments {
+	#[arg(long)]
+	pub destination: String,
+
+	#[arg(long)]
+	pub quiet: bool,
+
+	#[arg(long, default_value = "")]
+	pub manifest: String,	// Defaults to MANIFEST_FILE_NAME in the destination.
+}
+
+impl OutputArguments {
+	pub fn sanitize_destination(&mut self) {
+		self.destination.push_str("/"); // Make sure there's a trailing '/'
+	}
+}
+//
+// The counters a tool works on, flattened next to OutputArguments by the tools that can be limited to some of them.
+//
+#[derive(Args, Debug, Default, Clone)]
+pub struct SelectionArguments {
+	#[arg(long, default_value = "")]
+	pub nationality: String,	// Space separated groups, codes or aliases from NATIONALITIES_FILE (e.g., "german italian"), all when empty.
+
+	#[arg(long, default_value = "all")]
+	pub category: String,	// "gun", "inf", "veh" or "all".
+}
@@ -9,6 +9,7 @@ use crate::colors::*;
 use crate::common_record::*;
 use crate::defines::*;
 use crate::overrides::*;
+use crate::palettes::*;
 use crate::text_field::*;
 use crate::turret::*;
 use crate::utils::*;
@@ -229,7 +230,7 @@ impl Armament {
 		} else if gun.text.contains("BF") || gun.text.contains("TF") || gun.text.contains("SF") {
 			if gun.text.contains("<b>") {
 				gun.text = strip_html_bold(&gun.text);
-				self.color = RED.to_string();
+				self.color = palette_color(RED);
 			}
 
 			let char_vec: Vec<char> = gun.text.chars().collect();
@@ -634,8 +635,12 @@ pub fn generate_gun_caliber_line(counter_file: &std::fs::File, record: &CommonRe
 	return y_position;
 }
 
-pub fn generate_rof_element(mut counter_file: &std::fs::File, rof: &TextField, y_position: f64, color: &String, multiple_hits: bool) -> f64 {
+//
+// Intensive Fire capability is shown by underlining the ROF value.
+//
+pub fn generate_rof_element(mut counter_file: &std::fs::File, rof: &TextField, y_position: f64, color: &String, multiple_hits: bool, intensive_fire: bool) -> f64 {
 	let mut result = ROF_HEIGHT;
+	let text: String = if intensive_fire { format!("<tspan style=\"text-decoration:underline\">{0}</tspan>", rof.text) } else { rof.text.to_string() };
 	
 	generate_svg_start_element(counter_file, 1, GUN_COLUMN_X_POSITION, y_position - ROF_HEIGHT, ROF_HEIGHT, ROF_HEIGHT, "ROF", "white");
 
@@ -644,11 +649,11 @@ pub fn generate_rof_element(mut counter_file: &std::fs::File, rof: &TextField, y
 	}
 
 	if NoteAction::Prefix == rof.note.action {
-		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}{6}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, rof.note.text, rof.text).unwrap();
+		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}{6}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, rof.note.text, text).unwrap();
 	} else if NoteAction::Postfix == rof.note.action {
-		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}{6}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, rof.text, rof.note.text).unwrap();
+		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}{6}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, text, rof.note.text).unwrap();
 	} else {
-		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, rof.text).unwrap();
+		write!(counter_file, "\t\t<text x=\"50%\" y=\"{0}%\" dominant-baseline=\"auto\" text-anchor=\"middle\"><tspan style=\"font-size:{1:.2}px;{FONT_WEIGHT_BOLD};font-family:{2};font-family:{3};fill:{4};fill-opacity:1;stroke-width:0.2\">{5}</tspan></text>\n", rof.fonts.y_percentage(), rof.fonts.size(), FONT_MAIN, FONT_MAIN, color, text).unwrap();
 	}
 
 	write!(counter_file, "\t\t<rect x=\"{0:.2}\" y=\"{0:.2}\" width=\"{1:.2}\" height=\"{1:.2}\" style=\"display:inline;fill:none;fill-opacity:1;stroke:{2};stroke-width:{3:.2}px;stroke-dasharray:none;stroke-opacity:1\"/>\n", ROF_OFFSET, ROF_BOX_SIZE, color, ROF_STROKE_WIDTH).unwrap();
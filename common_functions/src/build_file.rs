@@ -0,0 +1,244 @@
+use std::collections::BTreeSet;
+use std::fs;
+use regex::Regex;
+//
+// Local files.
+//
+use crate::manifest::*;
+use crate::utils::*;
+
+pub const PIECE_SLOT_ELEMENT: &str =	"VASSAL.build.widget.PieceSlot";
+pub const LIST_WIDGET_ELEMENT: &str =	"VASSAL.build.widget.ListWidget";
+pub const PROTOTYPE_ELEMENT: &str =		"VASSAL.build.module.PrototypeDefinition";
+pub const ADD_PIECE: &str =				"+";	// The command that adds a piece, a saved game's or a PieceSlot's.
+
+const BASIC_PIECE: &str =		"piece;";
+const PROTOTYPE: &str =			"prototype;";
+const LAYER: &str =				"emb2;";
+const LAYER_ACTIVATE_KEY: &str =	"70,130";	// Ctrl+F, as the hand-made "Malf" layers of the review saves.
+const LAYER_NEXT_KEY: &str =		"86,130";	// Ctrl+V
+const BROKEN_LAYER: &str =		"BRK";		// VASL's "Flip broken units" looks at BRK_Active.
+const FLIP_LAYER: &str =		"Flip";
+//
+// The module's prototype each category's pieces use, they have to match a PrototypeDefinition of the buildFile (export_build_file
+// reports the ones that don't).
+//
+const CATEGORY_PROTOTYPES: [(&str, &str); 4] = [
+	( "veh",	"Vehicle" ),
+	( "gun",	"Ordnance" ),
+	( "inf",	"Infantry" ),
+	( "sw",		"SW" ),
+];
+//
+// VASSAL's SequenceEncoder: values joined by the delimiter, a delimiter inside a value is escaped with '\' and a value that starts
+// or ends with a quote is quoted.
+//
+pub fn encode_sequence(values: &[String], delimiter: char) -> String {
+	return values.iter().map(|value| {
+		let escaped: String = value.replace(delimiter, &format!("\\{delimiter}"));
+
+		if value.starts_with('\'') || value.ends_with('\'') { format!("'{escaped}'") } else { escaped }
+	}).collect::<Vec<String>>().join(&delimiter.to_string());
+}
+//
+// Traits (or their states) from the outermost to the basic piece, each wraps the ones after it.
+//
+pub fn encode_decorators(traits: &[String]) -> String {
+	let Some((result, outer)) = traits.split_last() else {
+		return Default::default();
+	};
+
+	return outer.iter().rev().fold(result.to_string(), |inner, decorator| encode_sequence(&[ decorator.to_string(), inner ], '\t'));
+}
+
+pub fn category_prototype(category: &String) -> Option<&'static str> {
+	return CATEGORY_PROTOTYPES.iter().find(|(name, _)| category == name).map(|(_, prototype)| *prototype);
+}
+//
+// The counter's <title> (its name in VASL) and width, from the generated file.
+//
+pub fn counter_title_and_size(path: &String) -> (Option<String>, u32) {
+	let text: String = fs::read_to_string(path).unwrap_or_default();
+	let title: Option<String> = Regex::new(r"<title>([^<]*)</title>").unwrap().captures(&text).map(|found| unescape_xml(&found[1]));
+	let size: Option<u32> = Regex::new(r#"<svg\b[^>]*?\bwidth="([\d.]+)(px)?""#).unwrap().captures(&text).and_then(|found| found[1].parse::<f64>().ok()).map(|width| width.round() as u32);
+
+	return (title, size.unwrap_or(60));
+}
+
+pub fn counter_size(path: &String) -> u32 {
+	return counter_title_and_size(path).1;
+}
+//
+// The back is a layer over the front, chosen by the front's category: guns and SW malfunction and MMC and SMC break (or are
+// wounded) with VASL's BRK layer, other backs (vehicles without their trailer ...) are a plain flip that shows the back's own name.
+//
+fn back_layer(front: &ManifestEntry, back: &ManifestEntry, back_name: &String) -> String {
+	let (command, level_name, layer): (&str, String, &str) = if "gun" == front.category || "sw" == front.category {
+		("Malf", "Malf +".to_string(), BROKEN_LAYER)
+	} else if "inf" == front.category {
+		("Broken", "Broken +".to_string(), BROKEN_LAYER)
+	} else {
+		(FLIP_LAYER, back_name.to_string(), FLIP_LAYER)
+	};
+	let fields: Vec<String> = [
+		command, "2", "F", "", "2", "V", "", "2", "", "", "", "0", "false", "0", "0",
+		&encode_sequence(&[ back.output.to_string() ], ','), &encode_sequence(&[ level_name ], ','),
+		"true", layer, "", "", "false", "", "1", "1", "false", LAYER_ACTIVATE_KEY, LAYER_NEXT_KEY, "", "", "1.0", "", "true",
+	].iter().map(|field| field.to_string()).collect();
+
+	return format!("{LAYER}{0}", encode_sequence(&fields, ';'));
+}
+//
+// The gpids VASSAL tells the pieces apart by ("<extensionId>:<n>" in an extension), without a prefix they're left empty for VASSAL's
+// editor to assign. A piece's number comes from its output path (FNV-1a), so that it keeps it from one version to the next whatever
+// else is added or removed: saved games find their pieces' slots by gpid. The rare clash takes the next free number.
+//
+const GPID_RANGE: u32 =	1 << 30;
+
+#[derive(Default)]
+pub struct PieceIds {
+	pub prefix: String,
+	pub used: BTreeSet<u32>,
+}
+
+impl PieceIds {
+	pub fn piece_id(&mut self, output: &String) -> String {
+		if self.prefix.is_empty() {
+			return Default::default();
+		}
+
+		let hash: u32 = output.bytes().fold(0x811c9dc5, |hash: u32, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+		let mut id: u32 = hash % GPID_RANGE;
+
+		while 0 == id || self.used.contains(&id) {
+			id = (id + 1) % GPID_RANGE;
+		}
+
+		self.used.insert(id);
+
+		return format!("{0}:{id}", self.prefix);
+	}
+	//
+	// The extension's nextPieceSlotId, past the numbers used.
+	//
+	pub fn next_free(&self) -> u32 {
+		return self.used.last().map(|id| id + 1).unwrap_or(1);
+	}
+}
+//
+// A piece's traits from the outermost to the basic piece (the category's prototype, its back as a layer, its image) and the states
+// of the decorators, with the name and size VASL shows it with. The basic piece's state (map, position, id ...) is given when the
+// piece is encoded, as a PieceSlot's piece isn't on a map yet.
+//
+pub struct PieceDefinition {
+	pub name: String,
+	pub size: u32,
+	pub traits: Vec<String>,
+	pub states: Vec<String>,
+}
+
+impl PieceDefinition {
+	//
+	// The counter (and its back).
+	//
+	pub fn counter(destination: &String, front: &ManifestEntry, back: Option<&ManifestEntry>) -> PieceDefinition {
+		let (title, size): (Option<String>, u32) = counter_title_and_size(&format!("{destination}{0}", front.output));
+		let name: String = title.unwrap_or(front.piece.to_string());
+		let mut result: PieceDefinition = PieceDefinition { name: name.to_string(), size, traits: Default::default(), states: Default::default() };
+
+		if let Some(prototype) = category_prototype(&front.category) {
+			result.traits.push(format!("{PROTOTYPE}{prototype}"));
+			result.states.push("".to_string());
+		}
+
+		if let Some(back) = back {
+			let (back_title, _) = counter_title_and_size(&format!("{destination}{0}", back.output));
+
+			result.traits.push(back_layer(front, back, &back_title.unwrap_or(back.piece.to_string())));
+			result.states.push("-1".to_string());
+		}
+
+		result.traits.push(format!("{BASIC_PIECE}{0}", encode_sequence(&[ "".to_string(), "".to_string(), front.output.to_string(), name ], ';')));
+
+		return result;
+	}
+	//
+	// A piece with only its image (no VASL traits).
+	//
+	pub fn image(image: &String, name: &String) -> PieceDefinition {
+		return PieceDefinition {
+			name: name.to_string(),
+			size: 0,
+			traits: vec![ format!("{BASIC_PIECE}{0}", encode_sequence(&[ "".to_string(), "".to_string(), image.to_string(), name.to_string() ], ';')) ],
+			states: Default::default(),
+		};
+	}
+	//
+	// The "+/<id>/<traits>/<states>" of a PieceSlot ("null" id, not on a map) or a saved game's AddPiece command.
+	//
+	pub fn add_piece(&self, id: &String, basic_state: &[String]) -> String {
+		let mut states: Vec<String> = self.states.clone();
+
+		states.push(encode_sequence(basic_state, ';'));
+
+		return encode_sequence(&[ ADD_PIECE.to_string(), id.to_string(), encode_decorators(&self.traits), encode_decorators(&states) ], '/');
+	}
+}
+//
+// A PieceSlot for the counter (and its back).
+//
+pub fn piece_slot(destination: &String, front: &ManifestEntry, back: Option<&ManifestEntry>, gpid: &String) -> String {
+	let piece: PieceDefinition = PieceDefinition::counter(destination, front, back);
+	let content: String = piece.add_piece(&"null".to_string(), &[ "null".to_string(), "0".to_string(), "0".to_string(), "".to_string(), "0".to_string() ]);
+
+	return format!("<{PIECE_SLOT_ELEMENT} entryName=\"{0}\" gpid=\"{2}\" height=\"{1}\" width=\"{1}\">{3}</{PIECE_SLOT_ELEMENT}>", escape_xml_attribute(&piece.name), piece.size, escape_xml_attribute(gpid), escape_xml_attribute(&content));
+}
+//
+// A ListWidget with the PieceSlots of a nationality and category, each preceded by the data file and line it comes from.
+//
+pub fn list_widget(destination: &String, title: &String, counters: &Vec<(&ManifestEntry, Option<&ManifestEntry>)>, ids: &mut PieceIds, indent: usize) -> String {
+	let tabs: String = "\t".repeat(indent);
+	let mut result: String = format!("{tabs}<{LIST_WIDGET_ELEMENT} entryName=\"{0}\">\n", escape_xml_attribute(title));
+
+	for (front, back) in counters {
+		result.push_str(&format!("{tabs}\t<!-- {0}, line {1} -->\n", escape_xml(&front.source), front.line));
+		result.push_str(&format!("{tabs}\t{0}\n", piece_slot(destination, front, *back, &ids.piece_id(&front.output))));
+	}
+
+	result.push_str(&format!("{tabs}</{LIST_WIDGET_ELEMENT}>\n"));
+
+	return result;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn strings(values: &[&str]) -> Vec<String> {
+		return values.iter().map(|value| value.to_string()).collect();
+	}
+
+	#[test]
+	fn encode_sequence_joins_the_values() {
+		assert_eq!(encode_sequence(&strings(&[ "piece", "", "", "ge/veh/x.svg", "x" ]), ';'), "piece;;;ge/veh/x.svg;x");
+		assert_eq!(encode_sequence(&[], ';'), "");
+	}
+
+	#[test]
+	fn encode_sequence_escapes_the_delimiter() {
+		assert_eq!(encode_sequence(&strings(&[ "a;b", "c" ]), ';'), "a\\;b;c");
+		assert_eq!(encode_sequence(&strings(&[ "a,b" ]), ';'), "a,b");
+	}
+
+	#[test]
+	fn encode_sequence_quotes_values_with_quotes_at_either_end() {
+		assert_eq!(encode_sequence(&strings(&[ "'a", "b'", "c'd" ]), ','), "''a',\'b'',c'd");
+	}
+
+	#[test]
+	fn encode_decorators_wraps_the_inner_traits() {
+		assert_eq!(encode_decorators(&strings(&[ "prototype;Vehicle", "piece;;;x.svg;x" ])), "prototype;Vehicle\tpiece;;;x.svg;x");
+		assert_eq!(encode_decorators(&strings(&[ "a", "b\tc", "d" ])), "a\tb\\\\\tc\\\td");
+		assert_eq!(encode_decorators(&[]), "");
+	}
+}
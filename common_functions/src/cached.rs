@@ -0,0 +1,108 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use sha2::{Digest, Sha256};
+//
+// Local files.
+//
+use crate::nationalities::*;
+
+pub const CACHED_DIRECTORY: &str =				"./cached/";
+pub const CACHED_COPY_DIRECTORY: &str =			"copy";		// Pre-made counters for rows with the "copy" override.
+pub const CACHED_DEPICTION_DIRECTORY: &str =	"svg";		// Unit depictions, copied as a whole.
+pub const CACHED_CATEGORIES: [&str; 3] =		[ "gun", "inf", "veh" ];
+
+#[derive(PartialEq)]
+#[derive(Clone, Copy)]
+pub enum CopyStatus {
+	Copied,
+	Unchanged,	// Same checksum, not rewritten.
+	Differs,	// Check mode only.
+	Missing,	// Check mode only.
+}
+
+impl fmt::Display for CopyStatus {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let text: &str = match self {
+			CopyStatus::Copied => "Copied",
+			CopyStatus::Unchanged => "Unchanged",
+			CopyStatus::Differs => "Differs",
+			CopyStatus::Missing => "Missing",
+		};
+
+		return write!(f, "{text}");
+	}
+}
+//
+// The cached tree has the destination's layout (see Nationality::output_folder()). Returns the cached folder and the matching folder
+// relative to the destination.
+//
+pub fn cached_directories(nationality: &String, category: &str) -> (String, String) {
+	let directory: String = nationality_folder(nationality, category);
+
+	return (format!("{CACHED_DIRECTORY}{directory}"), directory);
+}
+
+pub fn file_checksum(path: &String) -> io::Result<String> {
+	let contents: Vec<u8> = fs::read(path)?;
+
+	return Ok(format!("{:x}", Sha256::digest(&contents)));
+}
+//
+// All files below the directory (relative to it), sorted so that the output doesn't depend on the file system.
+//
+pub fn list_cached_files(directory: &String) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+
+	if let Ok(entries) = fs::read_dir(directory) {
+		for entry in entries.flatten() {
+			let name: String = entry.file_name().to_string_lossy().to_string();
+
+			if entry.path().is_dir() {
+				for file in list_cached_files(&format!("{directory}{name}/")) {
+					result.push(format!("{name}/{file}"));
+				}
+			} else {
+				result.push(name);
+			}
+		}
+	}
+
+	result.sort();
+
+	return result;
+}
+//
+// A missing source is an error (not a panic) so that the caller can name the offending row. Destinations with the same checksum
+// are left alone, new copies are verified against the source's checksum. In check mode nothing is written.
+//
+pub fn copy_cached_file(source: &String, destination: &String, check: bool) -> io::Result<CopyStatus> {
+	if !Path::new(source).is_file() {
+		return Err(io::Error::new(io::ErrorKind::NotFound, format!("cached source '{source}' not found")));
+	}
+
+	let checksum: String = file_checksum(source)?;
+
+	if Path::new(destination).is_file() {
+		if checksum == file_checksum(destination)? {
+			return Ok(CopyStatus::Unchanged);
+		} else if check {
+			return Ok(CopyStatus::Differs);
+		}
+	} else if check {
+		return Ok(CopyStatus::Missing);
+	}
+
+	if let Some(parent) = Path::new(destination).parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	fs::copy(source, destination)?;
+
+	if checksum != file_checksum(destination)? {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, format!("copy of '{source}' to '{destination}' doesn't match its checksum")));
+	}
+
+	return Ok(CopyStatus::Copied);
+}
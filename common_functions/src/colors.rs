@@ -1,22 +1,12 @@
 //
-// Color choices.
+// Local files.
+//
+use crate::nationalities::*;
+use crate::palettes::*;
+//
+// Color choices, nationality colors come from NATIONALITIES_FILE (see nationalities.rs). Colors written directly by the generators
+// (e.g., RED) go through palette_color() so that the palette selected with "--palette" applies to them (see palettes.rs).
 //
-pub const ALLIED_COLOR: &'static str =			"#82edbd";
-pub const AMERICAN_COLOR: &'static str =		"#cddb42"; // Original: "#cdf000";
-pub const AXIS_COLOR: &'static str =			"#1de256";
-pub const BRITISH_COLOR: &'static str =			"#e5cea0";
-pub const FINNISH_COLOR: &'static str =			"#ced3d3";
-pub const FRENCH_COLOR: &'static str =			"#41a5ff";
-pub const GERMAN_COLOR: &'static str =			"#91cdf5";
-pub const ITALIAN_COLOR: &'static str =			"#a6adb2";
-pub const JAPANESE_COLOR: &'static str =		"#ffdb00";
-pub const RUSSIAN_COLOR: &'static str =			"#d68d1a";
-pub const WAFFEN_SS_COLOR: &'static str =		"#000000";
-pub const LAVENDER_SS_COLOR: &'static str =		"#e7cef7";
-pub const SWEDISH_COLOR: &'static str =			"#629dcb";
-pub const BOAT_COLOR: &'static str =			"#91cdf5"; // Same as GERMAN_COLOR
-pub const SHARED_COLOR: &'static str =			"#ffffff";
-pub const LANDED_GLIDER_COLOR: &'static str =	"#52A552";
 pub const TEST_COLOR: &'static str =			"#ffc0ff";
 pub const UNDEFINED_COLOR: &'static str =		"";
 
@@ -79,121 +69,130 @@ impl Default for Colors {
 	}
 }
 
-pub fn nationality_to_color(nationality: &String) -> Vec<String> {
-	let background_color: &str;
-	let mut inner_background_color: &str = UNDEFINED_COLOR;
-
-	match nationality.as_str() {
-		"al" | "et"  => {
-			background_color = ALLIED_COLOR;
-		}
-		"am" | "us" => {
-			background_color = AMERICAN_COLOR;
-		}
-		"ax" => {
-			background_color = AXIS_COLOR;
-		}
-		"br" => {
-			background_color = BRITISH_COLOR;
-		}
-		"cc" => {
-			background_color = RUSSIAN_COLOR;
-			inner_background_color = BRITISH_COLOR;
-		}
-		"ch" => {
-			background_color = RUSSIAN_COLOR;
-			inner_background_color = GERMAN_COLOR;
-		}
-		"ff" | "fr" => {
-			background_color = FRENCH_COLOR;
-		}
-		"fi" => {
-			background_color = FINNISH_COLOR;
-		}
-		"ge" => {
-			background_color = GERMAN_COLOR;
-		}
-		"hu" => {
-			background_color = GERMAN_COLOR;
-			inner_background_color = AXIS_COLOR;
+impl Colors {
+	//
+	// Used by the "colors" column of NATIONALITIES_FILE, e.g., "turret_type=#b4b4b4|movement_type=#b4b4b4".
+	//
+	pub fn set_color(&mut self, field: &str, value: &str) {
+		if !self.try_set_color(field, value) {
+			panic!("Colors::set_color()@{0}: unknown color '{1}'", line!(), field);
 		}
-		"it" | "er" => {
-			background_color = ITALIAN_COLOR;
-		}
-		"ja" => {
-			background_color = JAPANESE_COLOR;
-		}
-		"nk" | "pa" | "ru" => {
-			background_color = RUSSIAN_COLOR;
-		}
-		"sh" => {
-			background_color = SHARED_COLOR;
-		}
-		"sk" => {
-			background_color = AMERICAN_COLOR;
-			inner_background_color = BRITISH_COLOR;
-		}
-		"ss" => {
-			background_color = WAFFEN_SS_COLOR;
-		}
-		"sv" => {
-			background_color = SWEDISH_COLOR;
-		}
-		"un" => {
-			background_color = AMERICAN_COLOR;
-			inner_background_color = FRENCH_COLOR;
-		}
-		"vf" => {
-			background_color = GERMAN_COLOR;
-			inner_background_color = FRENCH_COLOR;
-		}
-		"bt" => { // Landing craft and boats
-			background_color = BOAT_COLOR;
-		}
-		"gd" => {
-			background_color = LANDED_GLIDER_COLOR;
+	}
+	//
+	// Returns false (and changes nothing) when the field doesn't exist, palettes use that to tell fields from color values.
+	//
+	pub fn try_set_color(&mut self, field: &str, value: &str) -> bool {
+		let value: String = value.to_string();
+
+		match field {
+			"is_ss" => self.is_ss = "true" == value,
+			"background" => self.background = value,
+			"inner_background" => self.inner_background = value,
+			"turret_type" => self.turret_type = value,
+			"movement_type" => self.movement_type = value,
+			"movement_type_open_topped" => self.movement_type_open_topped = value,
+			"text" => self.text = value,
+			"movement_points_text" => self.movement_points_text = value,
+			"large_target" => self.large_target = value,
+			"normal_target" => self.normal_target = value,
+			"small_target" => self.small_target = value,
+			"small_target_circle" => self.small_target_circle = value,
+			"large_unarmored_target_stroke" => self.large_unarmored_target_stroke = value,
+			"large_unarmored_target_fill" => self.large_unarmored_target_fill = value,
+			"normal_unarmored_target_stroke" => self.normal_unarmored_target_stroke = value,
+			"normal_unarmored_target_fill" => self.normal_unarmored_target_fill = value,
+			"small_unarmored_target_stroke" => self.small_unarmored_target_stroke = value,
+			"small_unarmored_target_fill" => self.small_unarmored_target_fill = value,
+			"armor_modifier" => self.armor_modifier = value,
+			"manhandling_fill" => self.manhandling_fill = value,
+			"unhooking_penalty_color" => self.unhooking_penalty_color = value,
+			"malfunction_x" => self.malfunction_x = value,
+			&_ => return false,
 		}
-		"jk" => {	// Japanese/Korean ?
-			background_color = RUSSIAN_COLOR;
-			inner_background_color = JAPANESE_COLOR;
+
+		return true;
+	}
+	//
+	// Every color (not is_ss), for palettes that replace a color value wherever it is used.
+	//
+	pub fn color_values(&mut self) -> Vec<&mut String> {
+		return vec![
+			&mut self.background,
+			&mut self.inner_background,
+			&mut self.turret_type,
+			&mut self.movement_type,
+			&mut self.movement_type_open_topped,
+			&mut self.text,
+			&mut self.movement_points_text,
+			&mut self.large_target,
+			&mut self.normal_target,
+			&mut self.small_target,
+			&mut self.small_target_circle,
+			&mut self.large_unarmored_target_stroke,
+			&mut self.large_unarmored_target_fill,
+			&mut self.normal_unarmored_target_stroke,
+			&mut self.normal_unarmored_target_fill,
+			&mut self.small_unarmored_target_stroke,
+			&mut self.small_unarmored_target_fill,
+			&mut self.armor_modifier,
+			&mut self.manhandling_fill,
+			&mut self.unhooking_penalty_color,
+			&mut self.malfunction_x,
+		];
+	}
+}
+//
+// Background and inner background (UNDEFINED_COLOR for a plain counter), unknown nationalities get TEST_COLOR so they stand out.
+//
+fn nationality_backgrounds(nationality: &String) -> Colors {
+	let mut result: Colors = Default::default();
+
+	match find_nationality(nationality) {
+		Some(found) if !found.background.is_empty() => {
+			result.background = found.background.to_string();
+			result.inner_background = found.inner_background.to_string();
 		}
-		&_ => {
-			background_color = TEST_COLOR;
+		_ => {
+			result.background = TEST_COLOR.to_string();
+			result.inner_background = UNDEFINED_COLOR.to_string();
 		}
 	}
-	
+
+	return result;
+}
+//
+// Used for captured pieces, with the selected palette applied.
+//
+pub fn nationality_to_color(nationality: &String) -> Vec<String> {
 	let mut result: Vec<std::string::String> = Default::default();
+	let mut colors: Colors = nationality_backgrounds(nationality);
 
-	result.push(background_color.to_string());
-	result.push(inner_background_color.to_string());
+	apply_palette(&mut colors, nationality);
+
+	result.push(colors.background);
+	result.push(colors.inner_background);
 
 	return result;
 }
 
 pub fn nationality_to_colors(nationality: &String) -> Colors {
-	let mut result: Colors = Default::default();
-	let colors_v = nationality_to_color(nationality);
-	
-	result.background = colors_v[0].clone();
-	result.inner_background = colors_v[1].clone();
-
-	if "sh" == nationality {
-		result.movement_type = "#b4b4b4".to_string();
-		result.small_unarmored_target_stroke = "black".to_string();
-	} else if "ss" == nationality {
-		result.is_ss = true;
-		result.turret_type = "#b4b4b4".to_string();
-		result.movement_type = "#b4b4b4".to_string();
-		result.movement_type_open_topped = "#8ccdf5".to_string();
-		result.text = "white".to_string();
-		result.normal_target = "white".to_string();
-		result.small_target_circle = "#b4b4b4".to_string();
-		result.normal_unarmored_target_stroke = "white".to_string();
-		result.armor_modifier = "white".to_string();
-		result.manhandling_fill = "white".to_string();
-		result.unhooking_penalty_color = "white".to_string();
-		result.malfunction_x = "#b4b4b4".to_string();
+	let mut result: Colors = nationality_backgrounds(nationality);
+
+	if let Some(found) = find_nationality(nationality) {
+		if !found.text.is_empty() {
+			result.text = found.text.to_string();
+		}
+
+		for entry in found.colors.split(NATIONALITY_LIST_DELIMITER).filter(|entry| !entry.is_empty()) {
+			let Some((field, value)) = entry.split_once('=') else {
+				panic!("nationality_to_colors()@{0}: bad color entry '{1}' for '{2}'", line!(), entry, nationality);
+			};
+
+			result.set_color(field.trim(), value.trim());
+		}
 	}
+
+	apply_palette(&mut result, nationality);
 	
 	return result;
 }
@@ -5,6 +5,7 @@ use regex::Regex;
 use crate::armament::*;
 use crate::colors::*;
 use crate::malfunction::*;
+use crate::metadata::*;
 use crate::overrides::*;
 use crate::turret::*;
 use crate::utils::*;
@@ -27,6 +28,7 @@ pub struct CommonRecord {
 	pub comments: String,
 	pub colors: Colors,
 	pub svg_image_transform: String,
+	pub metadata: CounterMetadata,
 }
 
 impl CommonRecord {
@@ -0,0 +1,167 @@
+use std::error::Error;
+use std::io::prelude::*;
+use std::path::Path;
+use std::sync::Mutex;
+//
+// Local files.
+//
+use crate::arguments::*;
+use crate::cached::*;
+use crate::nationalities::*;
+use crate::text_field::*;
+use crate::utils::*;
+
+pub const MISSING_PLACEHOLDER: &str =	"placeholder";	// "--missing placeholder": draw a marked box instead of the depiction and carry on.
+pub const MISSING_STRICT: &str =		"strict";		// "--missing strict": fail the run, listing every missing depiction.
+
+pub const MISSING_COLOR: &str =			"#ff00ff";		// Not a counter color, so it can't be mistaken for art.
+
+pub const DEPICTION_DIRECTORY: &str =		"svg/";			// Next to the counters, the generated counters refer to "svg/<piece>.svg" (or ".png").
+pub const DEPICTION_FILE_TYPES: [&str; 2] =	[ ".svg", ".png" ];
+pub const CACHED_SHARED_DIRECTORIES: [&str; 1] =	[ "sh/" ];	// Depictions any nationality may use (relative to CACHED_DIRECTORY).
+pub const DEPICTION_SOURCE_ATTRIBUTE: &str =		"data-depiction";	// On the <image>, where its depiction was found.
+//
+// Where a depiction was found (source, for the reviewers) and how the counter refers to it (href, relative to the counter).
+//
+#[derive(Default)]
+pub struct Depiction {
+	pub source: String,
+	pub href: String,
+}
+
+impl Depiction {
+	pub fn is_png(&self) -> bool {
+		return self.href.ends_with(".png");
+	}
+	//
+	// Added to the image so that each counter tells where its depiction came from, an attribute rather than a comment as
+	// "--optimize" strips those.
+	//
+	pub fn source_attribute(&self) -> String {
+		return format!(" {DEPICTION_SOURCE_ATTRIBUTE}=\"{0}\"", escape_xml_attribute(&self.source));
+	}
+}
+//
+// The folders searched for "<filename>.svg" and then "<filename>.png", in order: the destination folder the counter is written to,
+// the same folder in the cached tree, the shared cached folders and the "--images" folders. "filename@xx" (see NOVR_IMAGE) looks in
+// nationality xx's folders instead.
+//
+fn depiction_search_path(root_path: &String, nationality: &String, args: &Arguments) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+	let mut relative: String = root_path.strip_prefix(&args.destination).unwrap_or(root_path).to_string();
+
+	if !nationality.is_empty() {
+		let code: String = nationality_code(nationality);
+
+		relative = match relative.split_once('/') {
+			Some((_, rest)) => format!("{code}/{rest}"),
+			None => format!("{code}/"),
+		};
+	}
+
+	result.push(format!("{0}{relative}{DEPICTION_DIRECTORY}", args.destination));
+	result.push(format!("{CACHED_DIRECTORY}{relative}{DEPICTION_DIRECTORY}"));
+
+	for shared in CACHED_SHARED_DIRECTORIES {
+		result.push(format!("{CACHED_DIRECTORY}{shared}{DEPICTION_DIRECTORY}"));
+	}
+
+	for directory in &args.images {
+		result.push(format!("{0}/", directory.trim_end_matches('/')));
+	}
+
+	return result;
+}
+//
+// Depictions found outside the counter's own "svg" folder are copied there (checksum verified), so the counter's reference stays
+// "svg/<file>" whichever folder the depiction came from.
+//
+pub fn resolve_depiction(root_path: &String, filename: &String, args: &Arguments) -> Option<Depiction> {
+	let (name, nationality) = match filename.split_once(NATIONALITY_DELIMITER) {
+		Some((name, nationality)) => (name.to_string(), nationality.to_string()),
+		None => (filename.to_string(), "".to_string()),
+	};
+
+	for directory in depiction_search_path(root_path, &nationality, args) {
+		for file_type in DEPICTION_FILE_TYPES {
+			let source: String = format!("{directory}{name}{file_type}");
+
+			if !Path::new(&source).is_file() {
+				continue;
+			}
+
+			let file: String = format!("{DEPICTION_DIRECTORY}{name}{file_type}");
+			let destination: String = format!("{root_path}{file}");
+
+			if source != destination {
+				if let Err(why) = copy_cached_file(&source, &destination, false) {
+					panic!("resolve_depiction()@{0}: couldn't copy '{1}' to '{2}': {3}", line!(), source, destination, why);
+				}
+			}
+
+			return Some(Depiction {
+				source: source.strip_prefix(&args.destination).unwrap_or(&source).to_string(),
+				href: file.replace(SPACE, "%20"),
+			});
+		}
+	}
+
+	return None;
+}
+//
+// Without "--missing" the generators keep their old behavior: vehicles and ordnance panic, the others draw nothing.
+//
+static MISSING_DEPICTIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn generate_missing_depiction_svg(mut counter_file: &std::fs::File, x: f64, y: f64, width: f64, height: f64, name: &String) {
+	let font_size: f64 = (width * 0.9 / (0.6 * name.chars().count().max(1) as f64)).min(width / 8.0);
+
+	write!(counter_file, "\t<!-- Missing depiction -->\n").unwrap();
+	write!(counter_file, "\t<svg x=\"{0:.2}\" y=\"{1:.2}\" width=\"{2:.2}\" height=\"{3:.2}\" style=\"overflow:visible\">\n", x, y, width, height).unwrap();
+	write!(counter_file, "\t\t<rect x=\"0.50\" y=\"0.50\" width=\"{0:.2}\" height=\"{1:.2}\" style=\"display:inline;fill:{2};fill-opacity:0.15;stroke:{2};stroke-width:1;stroke-dasharray:2,1;stroke-opacity:1\"/>\n", width - 1.0, height - 1.0, MISSING_COLOR).unwrap();
+	write!(counter_file, "\t\t<text x=\"50%\" y=\"45%\" text-anchor=\"middle\" style=\"font-size:{0:.2}px;font-weight:bold;font-family:{1};fill:{2};fill-opacity:1\">NO IMAGE</text>\n", width / 8.0, FONT_MAIN, MISSING_COLOR).unwrap();
+	write!(counter_file, "\t\t<text x=\"50%\" y=\"65%\" text-anchor=\"middle\" style=\"font-size:{0:.2}px;font-weight:normal;font-family:{1};fill:{2};fill-opacity:1\">{3}</text>\n", font_size, FONT_MAIN, MISSING_COLOR, escape_xml(name)).unwrap();
+	write!(counter_file, "\t</svg>\n").unwrap();
+}
+//
+// Called when neither "svg/<filename>.svg" nor "svg/<filename>.png" exists under root_path, the box is where the depiction would
+// have been. Returns false when no "--missing" mode is given so that the caller does what it always did.
+//
+pub fn missing_depiction(counter_file: &std::fs::File, root_path: &String, filename: &String, x: f64, y: f64, width: f64, height: f64, args: &Arguments) -> bool {
+	let missing: String = format!("{root_path}svg/{filename}.svg|.png");
+
+	if MISSING_PLACEHOLDER == args.missing {
+		println!("Warning: no depiction '{missing}', drawing a placeholder");
+		generate_missing_depiction_svg(counter_file, x, y, width, height, filename);
+	} else if MISSING_STRICT != args.missing {
+		if !args.missing.is_empty() {
+			panic!("missing_depiction()@{0}: unknown mode '{1}' (use '{2}' or '{3}')", line!(), args.missing, MISSING_PLACEHOLDER, MISSING_STRICT);
+		}
+
+		return false;
+	}
+
+	MISSING_DEPICTIONS.lock().unwrap().push(missing);
+
+	return true;
+}
+//
+// The generators call this last, in strict mode any missing depiction fails the run (after every counter has been written).
+//
+pub fn check_missing_depictions(args: &Arguments) -> Result<(), Box<dyn Error>> {
+	let missing = MISSING_DEPICTIONS.lock().unwrap();
+
+	if !args.missing.is_empty() && MISSING_PLACEHOLDER != args.missing && MISSING_STRICT != args.missing {
+		return Err(format!("Unknown \"--missing\" mode '{0}' (use '{MISSING_PLACEHOLDER}' or '{MISSING_STRICT}')", args.missing).into());
+	}
+
+	if MISSING_STRICT != args.missing || missing.is_empty() {
+		return Ok(());
+	}
+
+	for depiction in missing.iter() {
+		println!("Missing: {depiction}");
+	}
+
+	return Err(format!("{0} depiction(s) missing", missing.len()).into());
+}
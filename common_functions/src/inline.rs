@@ -0,0 +1,282 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use regex::{Captures, Regex};
+//
+// Local files.
+//
+use crate::arguments::*;
+use crate::manifest::*;
+
+pub const INLINE_ID_PREFIX: &str =	"depiction";	// Ids of an inlined SVG become "depiction<n>_<id>", <n> counts the counter's images.
+//
+// The image attributes that size the nested <svg>, the others (id, transform, style ...) go on the <g> that wraps it.
+//
+const INLINE_VIEWPORT_ATTRIBUTES: [&str; 5] =	[ "x", "y", "width", "height", "preserveAspectRatio" ];
+
+fn attributes(element: &str) -> Vec<(String, String)> {
+	let attribute = Regex::new(r#"([A-Za-z_][\w:.-]*)\s*=\s*"([^"]*)""#).unwrap();
+
+	return attribute.captures_iter(element).map(|found| (found[1].to_string(), found[2].to_string())).collect();
+}
+
+fn attribute_value(attributes: &Vec<(String, String)>, name: &str) -> Option<String> {
+	return attributes.iter().find(|(key, _)| name == key).map(|(_, value)| value.to_string());
+}
+//
+// Hrefs are relative to the counter (e.g., "svg/ge000H.png" or "./svg/CoL.svg"), spaces are written as "%20".
+//
+fn image_path(counter: &String, href: &String) -> PathBuf {
+	let directory: &Path = Path::new(counter).parent().unwrap_or(Path::new(""));
+
+	return directory.join(href.replace("%20", " "));
+}
+
+fn data_uri(path: &PathBuf, contents: &Vec<u8>) -> String {
+	let extension: String = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+	let mime_type: &str = match extension.as_str() {
+		"png" => "image/png",
+		"jpg" | "jpeg" => "image/jpeg",
+		"gif" => "image/gif",
+		_ => panic!("data_uri()@{0}: can't inline '{1}'", line!(), path.display()),
+	};
+
+	return format!("data:{mime_type};base64,{0}", STANDARD.encode(contents));
+}
+//
+// Every id of the depiction (and the "url(#id)" and "href=\"#id\"" references to it) gets the prefix, so that two depictions (or a
+// depiction and the counter) can't clash.
+//
+fn prefix_svg_ids(content: &String, prefix: &String) -> String {
+	let ids: BTreeSet<String> = Regex::new(r#"\bid\s*=\s*"([^"]+)""#).unwrap().captures_iter(content).map(|found| found[1].to_string()).collect();
+
+	return prefix_ids(content, &ids, prefix);
+}
+//
+// Only the given ids (and the references to them) get the prefix.
+//
+pub fn prefix_ids(content: &String, ids: &BTreeSet<String>, prefix: &String) -> String {
+	if ids.is_empty() {
+		return content.to_string();
+	}
+
+	let mut result: String = Regex::new(r#"\bid\s*=\s*"([^"]+)""#).unwrap().replace_all(content, |found: &Captures| {
+		if ids.contains(&found[1]) { format!("id=\"{prefix}{0}\"", &found[1]) } else { found[0].to_string() }
+	}).to_string();
+
+	result = Regex::new(r#"url\(\s*(['"]?)#([^)'"]+)(['"]?)\s*\)"#).unwrap().replace_all(&result, |found: &Captures| {
+		if ids.contains(&found[2]) { format!("url({0}#{prefix}{1}{2})", &found[1], &found[2], &found[3]) } else { found[0].to_string() }
+	}).to_string();
+
+	result = Regex::new(r##"href\s*=\s*"#([^"]+)""##).unwrap().replace_all(&result, |found: &Captures| {
+		if ids.contains(&found[1]) { format!("href=\"#{prefix}{0}\"", &found[1]) } else { found[0].to_string() }
+	}).to_string();
+
+	return result;
+}
+//
+// <image x y width height preserveAspectRatio transform ... href="x.svg"/> becomes
+// <g transform ...><svg x y width height preserveAspectRatio viewBox xmlns:...>(depiction)</svg></g>, the viewBox comes from the
+// depiction (or its width and height) so that it scales like the image did.
+//
+fn nested_svg(path: &PathBuf, image: &Vec<(String, String)>, prefix: &String) -> String {
+	let text: String = match fs::read_to_string(path) {
+		Err(why) => panic!("nested_svg()@{0}: couldn't read '{1}': {2}", line!(), path.display(), why),
+		Ok(text) => text,
+	};
+
+	return embed_svg(&text, &path.display().to_string(), image, prefix);
+}
+//
+// The SVG document (read from source) as a nested <svg> placed by the image attributes, also used to put counters on a sheet.
+//
+pub fn embed_svg(text: &String, source: &String, image: &Vec<(String, String)>, prefix: &String) -> String {
+	let root = Regex::new(r"<svg\b([^>]*)>").unwrap();
+	let Some(start) = root.captures(text) else {
+		panic!("embed_svg()@{0}: no <svg> element in '{1}'", line!(), source);
+	};
+	let Some(end) = text.rfind("</svg>") else {
+		panic!("embed_svg()@{0}: no </svg> in '{1}'", line!(), source);
+	};
+	let depiction: Vec<(String, String)> = attributes(&start[1]);
+	let content: String = prefix_svg_ids(&text[start.get(0).unwrap().end()..end].to_string(), prefix);
+	let mut group: String = "<g".to_string();
+	let mut viewport: String = "<svg".to_string();
+
+	for (name, value) in image {
+		if "href" == name || "xlink:href" == name {
+			continue;
+		} else if INLINE_VIEWPORT_ATTRIBUTES.contains(&name.as_str()) {
+			viewport.push_str(&format!(" {name}=\"{value}\""));
+		} else {
+			group.push_str(&format!(" {name}=\"{value}\""));
+		}
+	}
+
+	if let Some(view_box) = attribute_value(&depiction, "viewBox") {
+		viewport.push_str(&format!(" viewBox=\"{view_box}\""));
+	} else if let (Some(width), Some(height)) = (attribute_value(&depiction, "width"), attribute_value(&depiction, "height")) {
+		viewport.push_str(&format!(" viewBox=\"0 0 {0} {1}\"", width.trim_end_matches("px"), height.trim_end_matches("px")));
+	}
+	//
+	// Keep the namespace declarations so that inkscape:, sodipodi: ... attributes stay well formed.
+	//
+	for (name, value) in &depiction {
+		if name.starts_with("xmlns") {
+			viewport.push_str(&format!(" {name}=\"{value}\""));
+		}
+	}
+
+	return format!("{group}>{viewport}>{content}</svg></g>");
+}
+//
+// Replaces the counter's image references (the unit depiction, silhouettes, the Cross of Lorraine ...) with their contents: SVGs
+// become a nested <svg>, other images a base64 data URI. Used with "--inline" so that a counter renders without its "svg" folder.
+//
+pub fn inline_counter_images(counter: &String) {
+	let text: String = match fs::read_to_string(counter) {
+		Err(why) => panic!("inline_counter_images()@{0}: couldn't read '{1}': {2}", line!(), counter, why),
+		Ok(text) => text,
+	};
+	let (result, count): (String, usize) = inline_images(counter, &text);
+
+	if 0 != count {
+		if let Err(why) = fs::write(counter, result) {
+			panic!("inline_counter_images()@{0}: couldn't write '{1}': {2}", line!(), counter, why);
+		}
+	}
+}
+//
+// The counter's text (read from counter) with its images inlined, and how many were.
+//
+pub fn inline_images(counter: &String, text: &String) -> (String, usize) {
+	let image = Regex::new(r"<image\b([^>]*?)\s*(/>|></image>)").unwrap();
+	let mut count: usize = 0;
+
+	let result: String = image.replace_all(text, |found: &Captures| {
+		let element: Vec<(String, String)> = attributes(&found[1]);
+		let Some(href) = attribute_value(&element, "href").or_else(|| attribute_value(&element, "xlink:href")) else {
+			return found[0].to_string();
+		};
+
+		if href.starts_with("data:") || href.starts_with('#') {
+			return found[0].to_string();
+		}
+
+		let path: PathBuf = image_path(counter, &href);
+
+		//
+		// Some data refers to images that don't exist (yet), the counter keeps the reference so that it's no worse than without "--inline".
+		//
+		if !path.is_file() {
+			println!("{0}: '{1}' not found, left as a reference", counter, path.display());
+			return found[0].to_string();
+		}
+
+		count += 1;
+
+		if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("svg")) {
+			return nested_svg(&path, &element, &format!("{INLINE_ID_PREFIX}{count}_"));
+		}
+
+		let contents: Vec<u8> = match fs::read(&path) {
+			Err(why) => panic!("inline_images()@{0}: couldn't read '{1}': {2}", line!(), path.display(), why),
+			Ok(contents) => contents,
+		};
+
+		return found[0].replace(&format!("=\"{href}\""), &format!("=\"{0}\"", data_uri(&path, &contents)));
+	}).to_string();
+
+	return (result, count);
+}
+//
+// Called by the generators once all their counters are written (copied counters are left alone so that they still match their
+// cached checksum).
+//
+pub fn inline_generated_counters(args: &Arguments) {
+	if !args.inline {
+		return;
+	}
+
+	for counter in run_outputs(MANIFEST_GENERATED) {
+		inline_counter_images(&counter);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn image(attributes: &[(&str, &str)]) -> Vec<(String, String)> {
+		return attributes.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+	}
+	//
+	// The depiction nested_svg() reads, a file of its own per test.
+	//
+	fn depiction(test: &str, text: &str) -> PathBuf {
+		let result: PathBuf = std::env::temp_dir().join(format!("inline_{0}_{test}.svg", std::process::id()));
+
+		fs::write(&result, text).unwrap();
+
+		return result;
+	}
+
+	#[test]
+	fn prefix_svg_ids_renames_the_ids_and_their_references() {
+		let content: String = r##"<linearGradient id="a"/><rect id="b" style="fill:url(#a)" filter="url('#a')"/><use href="#b" xlink:href="#b"/><use href="#c"/>"##.to_string();
+
+		assert_eq!(prefix_svg_ids(&content, &"p_".to_string()), r##"<linearGradient id="p_a"/><rect id="p_b" style="fill:url(#p_a)" filter="url('#p_a')"/><use href="#p_b" xlink:href="#p_b"/><use href="#c"/>"##);
+	}
+
+	#[test]
+	fn prefix_svg_ids_keeps_numbers_in_ids() {
+		let content: String = r##"<filter id="blur1.50"/><rect style="filter:url(#blur1.50)"/>"##.to_string();
+
+		assert_eq!(prefix_svg_ids(&content, &"p_".to_string()), r##"<filter id="p_blur1.50"/><rect style="filter:url(#p_blur1.50)"/>"##);
+	}
+
+	#[test]
+	fn prefix_svg_ids_leaves_content_without_ids_alone() {
+		let content: String = r##"<rect style="fill:url(#a)"/><image href="svg/a.svg"/>"##.to_string();
+
+		assert_eq!(prefix_svg_ids(&content, &"p_".to_string()), content);
+	}
+
+	#[test]
+	fn nested_svg_places_the_depiction_like_the_image() {
+		let path: PathBuf = depiction("places", r##"<?xml version="1.0"?><svg width="100" height="50" viewBox="0 0 200 100" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"><path id="p" inkscape:label="x"/><use href="#p"/></svg>"##);
+		let element: Vec<(String, String)> = image(&[ ("id", "Silhouettes"), ("x", "0"), ("y", "0"), ("width", "48"), ("height", "48"), ("preserveAspectRatio", "xMidYMid meet"), ("transform", "scale(1.00)"), ("href", "svg/x.svg"), ("xlink:href", "svg/x.svg") ]);
+
+		assert_eq!(nested_svg(&path, &element, &"depiction1_".to_string()), r##"<g id="Silhouettes" transform="scale(1.00)"><svg x="0" y="0" width="48" height="48" preserveAspectRatio="xMidYMid meet" viewBox="0 0 200 100" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"><path id="depiction1_p" inkscape:label="x"/><use href="#depiction1_p"/></svg></g>"##);
+	}
+
+	#[test]
+	#[should_panic(expected = "no <svg> element")]
+	fn nested_svg_needs_an_svg_document() {
+		nested_svg(&depiction("html", "<html></html>"), &Default::default(), &"depiction1_".to_string());
+	}
+
+	#[test]
+	fn data_uri_encodes_the_image() {
+		assert_eq!(data_uri(&PathBuf::from("svg/ge000H.png"), &b"PNG".to_vec()), "data:image/png;base64,UE5H");
+		assert_eq!(data_uri(&PathBuf::from("svg/CoL.GIF"), &Default::default()), "data:image/gif;base64,");
+	}
+
+	#[test]
+	fn prefix_ids_only_renames_the_given_ids() {
+		let content: String = r##"<rect id="a" style="fill:url(#ab)"/><rect id="ab"/><use href="#a"/><use href="#ab"/>"##.to_string();
+
+		assert_eq!(prefix_ids(&content, &BTreeSet::from([ "a".to_string() ]), &"p_".to_string()), r##"<rect id="p_a" style="fill:url(#ab)"/><rect id="ab"/><use href="#p_a"/><use href="#ab"/>"##);
+		assert_eq!(prefix_ids(&content, &Default::default(), &"p_".to_string()), content);
+	}
+
+	#[test]
+	fn embed_svg_sizes_the_view_box_without_one() {
+		let text: String = r#"<svg width="60px" height="30px"><rect/></svg>"#.to_string();
+		let element: Vec<(String, String)> = image(&[ ("width", "48"), ("height", "24") ]);
+
+		assert_eq!(embed_svg(&text, &"x.svg".to_string(), &element, &"depiction1_".to_string()), r#"<g><svg width="48" height="24" viewBox="0 0 60 30"><rect/></svg></g>"#);
+	}
+}
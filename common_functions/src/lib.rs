@@ -1,5 +1,4 @@
 use std::io::prelude::*;
-use std::path::Path;
 // Deprecated use chrono::Utc;
 use regex::Regex;
 //
@@ -8,14 +7,26 @@ use regex::Regex;
 pub mod arguments;
 pub mod armament;
 pub mod armor;
+pub mod build_file;
+pub mod cached;
 pub mod debugging;
 pub mod defines;
+pub mod depictions;
+pub mod inline;
 pub mod common_record;
 pub mod colors;
 pub mod machine_guns;
 pub mod malfunction;
+pub mod manifest;
+pub mod markings;
+pub mod metadata;
 pub mod movement;
+pub mod nationalities;
+pub mod optimize;
 pub mod overrides;
+pub mod palettes;
+pub mod profiles;
+pub mod smoke;
 pub mod special;
 pub mod text;
 pub mod text_field;
@@ -26,8 +37,13 @@ pub mod utils;
 use crate::arguments::*;
 use crate::colors::*;
 use crate::debugging::*;
+use crate::depictions::*;
 use crate::defines::*;
+use crate::manifest::*;
+use crate::metadata::*;
 use crate::overrides::*;
+use crate::palettes::*;
+use crate::profiles::*;
 use crate::text_field::*;
 //
 // Values for a 1000 x 1000 pixel image.
@@ -46,8 +62,9 @@ pub const BEVEL_SHADOW_LOW: f32 =		 10.0;
 
 pub const AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const TESTERS: &str = "Alan Bills, Alan Cannamore, Doug Rimmer";
 
-fn generate_bevel_svg_elements(mut counter_file: &std::fs::File) {
+pub fn generate_bevel_svg_elements(mut counter_file: &std::fs::File) {
 	
 	write!(counter_file, "\t<!-- The bevel - is there a better/more efficient way to achieve this? -->\n").unwrap();
 	write!(counter_file, "\t<defs id=\"bevel\">\n").unwrap();
@@ -88,8 +105,14 @@ pub fn generate_counter_background_svg(mut counter_file: &std::fs::File, size: u
 		y_translate = 310.0;		
 	}
 
+	let extent: f64 = rectangle_size;
+
 	if CREATE_BEVEL {
-		generate_bevel_svg_elements(counter_file);
+		if PROFILE_FLAT == render_profile() {
+			generate_flat_bevel_svg_elements(counter_file);
+		} else {
+			generate_bevel_svg_elements(counter_file);
+		}
 	}
 	
 	write!(counter_file, "\t<g id=\"background\">\n").unwrap();
@@ -111,14 +134,18 @@ pub fn generate_counter_background_svg(mut counter_file: &std::fs::File, size: u
 	}
 
 	if overrides.striped {
+		let red: String = palette_color(RED);
+
 		if !colors.inner_background.is_empty() {
-			write!(counter_file, "\t\t<rect x=\"3.60\" y=\"34\" width=\"40.8\" height=\"10.40\" style=\"display:inline;fill:red;fill-opacity:{opacity};stroke:none;stroke-width:0;stroke-dasharray:none;stroke-opacity:1\"/>\n").unwrap();
+			write!(counter_file, "\t\t<rect x=\"3.60\" y=\"34\" width=\"40.8\" height=\"10.40\" style=\"display:inline;fill:{red};fill-opacity:{opacity};stroke:none;stroke-width:0;stroke-dasharray:none;stroke-opacity:1\"/>\n").unwrap();
 		} else {
-			write!(counter_file, "\t\t<rect x=\"0.00\" y=\"34\" width=\"48.00\" height=\"10.40\" style=\"display:inline;fill:red;fill-opacity:{opacity};stroke:none;stroke-width:0;stroke-dasharray:none;stroke-opacity:1\"/>\n").unwrap();
+			write!(counter_file, "\t\t<rect x=\"0.00\" y=\"34\" width=\"48.00\" height=\"10.40\" style=\"display:inline;fill:{red};fill-opacity:{opacity};stroke:none;stroke-width:0;stroke-dasharray:none;stroke-opacity:1\"/>\n").unwrap();
 		}
 	}
 	
-	if CREATE_BEVEL {
+	if CREATE_BEVEL && PROFILE_FLAT == render_profile() {
+		generate_flat_bevel_svg(counter_file, extent);
+	} else if CREATE_BEVEL {
 		write!(counter_file, "\t\t<g id=\"bevel\" clip-path=\"url(#counter_clipping)\">\n").unwrap();
 		write!(counter_file, "\t\t\t<path id=\"shadow\" style=\"display:inline;fill:none;stroke:#000000;stroke-width:{BEVEL_WIDTH_MEDIUM};filter:url(#shadow_filter);stroke-opacity:{BEVEL_SHADOW_MEDIUM}\" d=\"m 1023.6424,120.45592 c 10.8149,10.81495 17.4864,25.7734 17.4864,42.34736 v 830.07515 c 0,33.14797 -26.6859,59.83377 -59.83378,59.83377 H 151.21981 c -16.57396,0 -31.54851,-6.6875 -42.34737,-17.4864\" transform=\"matrix(1.0503456,0,0,1.0590455,-36.912259,-39.56161) scale(0.06) translate({0},{1})\"/>\n", x_translate, y_translate).unwrap();
 		write!(counter_file, "\t\t\t<path id=\"highlight\" style=\"display:inline;fill:none;stroke:#ffffff;stroke-width:{BEVEL_WIDTH_MEDIUM};stroke-opacity:{BEVEL_HIGHLIGHT_MEDIUM};filter:url(#highlight_filter)\" d=\"M 108.87244,1035.2258 C 98.057485,1024.4109 91.386017,1009.4524 91.386017,992.87843 V 162.80328 c 0,-33.14792 26.685873,-59.83379 59.833793,-59.83379 h 830.07521 c 16.57394,0 31.53238,6.67147 42.34738,17.48643\" transform=\"matrix(1.0387133,0,0,1.0339712,-12.412385,-8.9484139) scale(0.06) translate(118,54)\"/>\n").unwrap();		
@@ -132,24 +159,12 @@ pub fn generate_counter_background_svg(mut counter_file: &std::fs::File, size: u
 
 pub fn generate_unit_depiction_svg(mut counter_file: &std::fs::File, root_path: &String, filename: &String, note: &String, svg_transform: &String, front: bool, name: &String, display_name: bool, colors: &Colors, args: &Arguments) {
 	if INCLUDE_IMAGES {
-		let mut found = false;
-		let path_prefix = "svg/";
-		let file_type_svg = ".svg";
-		let file_type_png = ".png";
-
-		let paths: Vec<std::string::String> = [
-			format!("{}{}{}", path_prefix, filename, file_type_svg),
-			format!("{}{}{}", path_prefix, filename, file_type_png)
-		].to_vec();
-
-		for mut path in paths {
-			let mut pathname: String = root_path.to_string();
-			pathname.push_str(&path.to_string());
-
-			if Path::new(&pathname).exists() {
+		match resolve_depiction(root_path, filename, args) {
+			Some(depiction) => {
+				let depiction_source: String = depiction.source_attribute();
 				let mut transform: String = Default::default();
 
-				if pathname.contains(".png") { // Temporary (eventually).
+				if depiction.is_png() { // Temporary (eventually).
 					if !front {
 						transform = "rotate(-90, 30, 30)".to_string();
 					}
@@ -159,19 +174,14 @@ pub fn generate_unit_depiction_svg(mut counter_file: &std::fs::File, root_path:
 					transform = "scale(1.00) translate(0, 0) rotate(60, 30, 30)".to_string();
 				}
 
-				if path.contains(SPACE) {
-					path = path.replace(SPACE, "%20");
-				}
-
 				write!(counter_file, "\t<!-- Unit depiction -->\n").unwrap();
-				write!(counter_file, "\t<image x=\"0\" y=\"0\" width=\"60\" height=\"60\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{0}\" href=\"{1}\" xlink:href=\"{1}\"/>\n", transform, path).unwrap();
-				found = true;
-				break;	// Our work here is done.
+				write!(counter_file, "\t<image x=\"0\" y=\"0\" width=\"60\" height=\"60\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{0}\" href=\"{1}\" xlink:href=\"{1}\"{depiction_source}/>\n", transform, depiction.href).unwrap();
+			}
+			None => {
+				if !missing_depiction(counter_file, root_path, filename, 15.0, 15.0, 30.0, 30.0, args) {
+					panic!("generate_unit_depiction_svg() {filename} not found!")
+				}
 			}
-		}
-
-		if false == found {
-			panic!("generate_unit_depiction_svg() {filename} not found!")
 		}
 	}
 
@@ -207,7 +217,11 @@ pub fn generate_unit_depiction_svg_elements(mut counter_file: &std::fs::File, no
 	}	
 }
 
-pub fn generate_counter_header_svg_elements(program_name: &'static str, mut counter_file: &std::fs::File, size: u32, name: &String, note_number: &String, comment: &String, version: &String) {
+//
+// The counter's name, note number and data version also go into the metadata block (see metadata.rs), so callers only fill in
+// the unit-specific fields (piece, nationality, unit type, Chapter H values).
+//
+pub fn generate_counter_header_svg_elements(program_name: &'static str, mut counter_file: &std::fs::File, size: u32, name: &String, note_number: &String, comment: &String, version: &String, metadata: &CounterMetadata) {
 	write!(counter_file, "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n").unwrap();
 	write!(counter_file, "<svg width=\"{size}\" height=\"{size}\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:svg=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">\n").unwrap();
 	write!(counter_file, "\t<!--\n").unwrap();
@@ -249,7 +263,7 @@ pub fn generate_counter_header_svg_elements(program_name: &'static str, mut coun
 	}
 
 	write!(counter_file, "{0}\n", authors_list).unwrap();
-	write!(counter_file, "\t\tTester(s): Alan Bills, Alan Cannamore, Doug Rimmer\n").unwrap();
+	write!(counter_file, "\t\tTester(s): {TESTERS}\n").unwrap();
 	
 	let mut filename: String = Default::default();
 	
@@ -265,6 +279,17 @@ pub fn generate_counter_header_svg_elements(program_name: &'static str, mut coun
 
 	write!(counter_file, "\t-->\n\n").unwrap();
 
+	let mut counter_metadata: CounterMetadata = metadata.clone();
+
+	counter_metadata.format = METADATA_FORMAT.to_string();
+	counter_metadata.name = strip_html_tags(name);
+	counter_metadata.note = note_number.to_string();
+	counter_metadata.data_version = version.to_string();
+	counter_metadata.source_row = manifest_source_row();
+	counter_metadata.generator = program_name.to_string();
+	counter_metadata.generator_version = VERSION.to_string();
+	counter_metadata.generate_svg_elements(&counter_file);
+
 	embed_fonts_svg(&counter_file);
 }
 
@@ -324,7 +349,7 @@ fn embed_fonts_svg(mut counter_file: &std::fs::File) {
 		write!(counter_file, "\t</defs>\n").unwrap();
 	} else if LINK_FONTS {
 		write!(counter_file, "\t<!-- Linked Fonts. -->\n").unwrap();
-		write!(counter_file, "\t<link xmlns=\"http://ww.w3.org/1999/xhtml\" rel=\"stylesheet\" href=\"../../fonts.svg\" type=\"text/css\"/>\n").unwrap();
-		write!(counter_file, "\t<link xmlns=\"http://ww.w3.org/1999/xhtml\" rel=\"stylesheet\" href=\"../fonts.svg\" type=\"text/css\"/>\n\n").unwrap();
+		write!(counter_file, "\t<link xmlns=\"http://www.w3.org/1999/xhtml\" rel=\"stylesheet\" href=\"../../fonts.svg\" type=\"text/css\"/>\n").unwrap();
+		write!(counter_file, "\t<link xmlns=\"http://www.w3.org/1999/xhtml\" rel=\"stylesheet\" href=\"../fonts.svg\" type=\"text/css\"/>\n\n").unwrap();
 	}
 }
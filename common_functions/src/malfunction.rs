@@ -1,10 +1,12 @@
 use std::write;
 use std::io::prelude::*;
+use regex::Regex;
 // Local files.
 //
 use crate::colors::*;
 use crate::defines::*;
 use crate::overrides::*;
+use crate::palettes::*;
 use crate::text_field::*;
 use crate::utils::*;
 
@@ -19,6 +21,85 @@ pub const BREAKDOWN_FONTS: [[f64; 4]; 8] = [
 	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_BOTHLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
 ];
 
+//
+// Malfunctioned SW back side tokens (the "counter" column), also generated from a front's "repair" column.
+//
+pub const BACK_TOKEN_MG: &str =		"_mg";
+pub const BACK_TOKEN_MTR: &str =	"_mtr";
+pub const BACK_TOKEN_ATR: &str =	"_atr";
+
+pub const BACK_PIECE_SUFFIX: &str =	"b";	// The generated back is named after the front, e.g., "geLMG" and "geLMGb".
+
+pub const LIGHT_MORTAR_CALIBER: u32 =	60;	// The largest mortar that's a (non-dm) light mortar, in millimeters.
+//
+// The caliber is the number of the "value" column, e.g., "<tspan style="text-decoration:overline">50</tspan>&#x273D;" (0 when
+// there isn't one).
+//
+pub fn mortar_caliber(value: &str) -> u32 {
+	let markup = Regex::new(r"<[^>]*>|&#x[0-9a-fA-F]+;|&#[0-9]+;").unwrap();
+	let caliber = Regex::new(r"[0-9]+").unwrap();
+	let text: String = markup.replace_all(value, "").to_string();
+
+	return caliber.find(&text).map(|found| found.as_str().parse::<u32>().unwrap_or(0)).unwrap_or(0);
+}
+//
+// Machine guns, light mortars and ATRs (not the dismantled ones) have a malfunctioned back side, "" for the other SWs.
+//
+pub fn sw_malfunctioned_back_token(counter: &str, value: &str) -> &'static str {
+	let mut result: &'static str = "";
+
+	if !counter.starts_with("dm") {
+		if counter.contains("MG") {
+			result = BACK_TOKEN_MG;
+		} else if counter.contains("MTR") {
+			let caliber: u32 = mortar_caliber(value);
+
+			if 0 != caliber && LIGHT_MORTAR_CALIBER >= caliber {
+				result = BACK_TOKEN_MTR;
+			}
+		} else if counter.contains("ATR") {
+			result = BACK_TOKEN_ATR;
+		}
+	}
+
+	return result;
+}
+//
+// The SW "repair" column, e.g., "1/6" (R1, X6) or "3" (R3, X3): the repair and disable numbers, plus their texts when neither
+// is a number (e.g., "&#x2620;/&#x2620;").
+//
+pub fn split_sw_repair(repair: &str) -> (usize, usize, String, String) {
+	let mut result: (usize, usize, String, String) = Default::default();
+
+	if !repair.is_empty() {
+		if let Some((left, right)) = repair.split_once('/') {
+			result.0 = left.parse::<usize>().unwrap_or(0);
+			result.1 = right.parse::<usize>().unwrap_or(0);
+
+			if 0 == result.0 && 0 == result.1 {
+				result.2 = left.to_string();
+				result.3 = right.to_string();
+			}
+		} else {
+			result.0 = repair.parse::<usize>().unwrap_or(0);
+			result.1 = result.0;
+		}
+	}
+
+	return result;
+}
+//
+// The malfunctioned back side token of a SW front with the given repair number or text (see split_sw_repair()), "" when it
+// doesn't have one.
+//
+pub fn sw_repair_back_token(counter: &str, value: &str, repair: usize, repair_text: &str) -> &'static str {
+	if 0 == repair && repair_text.is_empty() {
+		return "";
+	}
+
+	return sw_malfunctioned_back_token(counter, value);
+}
+
 #[derive(PartialEq)]
 #[derive(Default)]
 pub struct Malf {
@@ -74,7 +155,7 @@ impl Malf {
 
 		if temp.contains("<b>") {
 			temp = strip_html_bold(&temp);
-			self.value.color = RED.to_string();
+			self.value.color = palette_color(RED);
 		} else {
 			self.value.color = colors.text.to_string();
 		}
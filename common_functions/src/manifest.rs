@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+//
+// Local files.
+//
+use crate::arguments::*;
+use crate::nationalities::*;
+
+pub const MANIFEST_FILE_NAME: &str =	"manifest.csv";	// Written to the destination unless "--manifest" says otherwise.
+pub const MANIFEST_STDIN_SOURCE: &str =	"stdin";		// Used when "--source" isn't given.
+
+pub const MANIFEST_FRONT: &str =		"front";
+pub const MANIFEST_BACK: &str =			"back";
+
+pub const MANIFEST_GENERATED: &str =	"generated";
+pub const MANIFEST_COPIED: &str =		"copied";
+
+pub const LEGACY_EXTENSION: &str =		"gif";	// VASL's bitmap counters, next to the SVGs in its images folder.
+//
+// One row per output file, the category ("veh", "gun", "inf", "sw", "misc") and source file are filled in by write_manifest(). A
+// front's "back" is the output of its back side (empty when it has none), several fronts can share one (broken MMC).
+//
+#[derive(Serialize, Deserialize)]
+#[derive(Clone)]
+pub struct ManifestEntry {
+	pub output: String,
+	pub source: String,
+	pub line: u64,
+	pub piece: String,
+	pub nationality: String,
+	pub category: String,
+	pub side: String,
+	#[serde(default)]
+	pub back: String,
+	pub action: String,
+}
+//
+// Collects the entries of the current run, keyed by output path so that the manifest is always sorted (and a counter written twice
+// is only listed once).
+//
+struct RunManifest {
+	line: u64,
+	row: String,	// The row's fingerprint (see row_fingerprint()).
+	entries: BTreeMap<String, ManifestEntry>,
+	backs: BTreeMap<String, Vec<String>>,	// The pieces that can be a front's back, by output path (see add_manifest_backs()).
+}
+
+static RUN_MANIFEST: Mutex<RunManifest> = Mutex::new(RunManifest { line: 0, row: String::new(), entries: BTreeMap::new(), backs: BTreeMap::new() });
+//
+// Called for each CSV row before its counters are generated or copied. The row's fingerprint goes into the metadata of the counters
+// generated from it, so that check_counter_outputs can tell the ones made from an older version of their row.
+//
+pub fn set_manifest_source_row(row: &csv::StringRecord) {
+	let mut manifest = RUN_MANIFEST.lock().unwrap();
+
+	manifest.line = row.position().map(|position| position.line()).unwrap_or(0);
+	manifest.row = row_fingerprint(row);
+}
+
+pub fn manifest_source_row() -> String {
+	return RUN_MANIFEST.lock().unwrap().row.to_string();
+}
+
+pub fn manifest_source_line() -> u64 {
+	return RUN_MANIFEST.lock().unwrap().line;
+}
+//
+// FNV-1a of the row's fields as the csv crate reads them, so that quoting and line endings don't change it.
+//
+pub fn row_fingerprint(row: &csv::StringRecord) -> String {
+	let hash: u64 = row.iter().flat_map(|field| field.bytes().chain([ 0x1f ])).fold(0xcbf29ce484222325, |hash: u64, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+
+	return format!("{hash:016x}");
+}
+
+pub fn add_manifest_entry(path: &String, piece: &String, nationality: &String, side: &str, action: &str) {
+	let mut manifest = RUN_MANIFEST.lock().unwrap();
+	let output: String = format!("{path}{piece}.svg");
+	let entry: ManifestEntry = ManifestEntry {
+		output: output.to_string(),
+		source: Default::default(),
+		line: manifest.line,
+		piece: piece.to_string(),
+		nationality: nationality.to_string(),
+		category: Default::default(),
+		side: side.to_string(),
+		back: Default::default(),
+		action: action.to_string(),
+	};
+
+	manifest.entries.insert(output, entry);
+}
+//
+// The pieces (in the front's folder) that can be the back of a counter, in order of preference. The first one the manifest has as
+// a back is recorded, "<piece>b" is tried before them (see write_manifest()).
+//
+pub fn add_manifest_backs(path: &String, piece: &String, backs: &Vec<String>) {
+	let mut manifest = RUN_MANIFEST.lock().unwrap();
+
+	manifest.backs.entry(format!("{path}{piece}.svg")).or_default().extend(backs.iter().map(|back| format!("{path}{back}.svg")));
+}
+
+//
+// The files written (or copied) by this run so far, as given to add_manifest_entry().
+//
+pub fn run_outputs(action: &str) -> Vec<String> {
+	return RUN_MANIFEST.lock().unwrap().entries.iter().filter(|(_, entry)| action == entry.action).map(|(output, _)| output.to_string()).collect();
+}
+
+pub fn manifest_path(args: &Arguments) -> String {
+	return manifest_file(&args.destination, &args.manifest);
+}
+
+pub fn manifest_file(destination: &String, manifest: &String) -> String {
+	if !manifest.is_empty() {
+		return manifest.to_string();
+	}
+
+	return format!("{destination}{MANIFEST_FILE_NAME}"); // destination includes a trailing '/'
+}
+
+pub fn read_manifest(path: &String) -> Vec<ManifestEntry> {
+	let mut result: Vec<ManifestEntry> = Default::default();
+
+	if !Path::new(path).exists() {
+		return result;
+	}
+
+	let mut rdr = match csv::Reader::from_path(path) {
+		Err(why) => panic!("read_manifest()@{0}: couldn't open manifest '{1}': {2}", line!(), path, why),
+		Ok(rdr) => rdr,
+	};
+
+	for entry in rdr.deserialize() {
+		match entry {
+			Err(why) => panic!("read_manifest()@{0}: bad entry in manifest '{1}': {2}", line!(), path, why),
+			Ok(entry) => result.push(entry),
+		}
+	}
+
+	return result;
+}
+//
+// The tools that work on the generated counters (gallery, sheets, buildFile ...) can't do without the manifest.
+//
+pub fn required_manifest(outputs: &OutputArguments) -> Vec<ManifestEntry> {
+	let path: String = manifest_file(&outputs.destination, &outputs.manifest);
+	let result: Vec<ManifestEntry> = read_manifest(&path);
+
+	if result.is_empty() {
+		panic!("required_manifest()@{0}: no manifest '{1}' (or it's empty), run run_all.sh first", line!(), path);
+	}
+
+	return result;
+}
+//
+// Output paths are stored relative to the destination. Rows from earlier runs (other generators, other CSV files) are kept unless
+// this run wrote the same file, everything is sorted by output path so that the manifest doesn't depend on the order of the runs.
+//
+pub fn write_manifest(category: &str, args: &Arguments) {
+	let path: String = manifest_path(args);
+	let source: String = if !args.source.is_empty() { args.source.to_string() } else { MANIFEST_STDIN_SOURCE.to_string() };
+	let mut entries: BTreeMap<String, ManifestEntry> = Default::default();
+
+	for entry in read_manifest(&path) {
+		entries.insert(entry.output.to_string(), entry);
+	}
+
+	let manifest = RUN_MANIFEST.lock().unwrap_or_else(|poisoned| poisoned.into_inner());	// Still list the rows of a run that panicked.
+	let relative = |output: &String| -> String { output.strip_prefix(&args.destination).unwrap_or(output).to_string() };
+	let mut fronts: BTreeMap<String, Vec<String>> = Default::default();
+
+	for entry in manifest.entries.values() {
+		let mut entry: ManifestEntry = entry.clone();
+
+		entry.output = relative(&entry.output);
+		entry.source = source.to_string();
+		entry.category = category.to_string();
+
+		if MANIFEST_FRONT == entry.side {
+			let mut backs: Vec<String> = vec![ format!("{0}b.svg", entry.output.strip_suffix(".svg").unwrap_or(&entry.output)) ];
+
+			backs.extend(manifest.backs.get(&format!("{0}{1}", args.destination, entry.output)).into_iter().flatten().map(relative));
+			fronts.insert(entry.output.to_string(), backs);
+		}
+
+		entries.insert(entry.output.to_string(), entry);
+	}
+
+	drop(manifest);
+	//
+	// A copied "<piece>b" is the back of the counter "<piece>" next to it (generated or copied, by this run or an earlier one).
+	//
+	let backs: Vec<String> = entries.values().filter(|entry| {
+		MANIFEST_COPIED == entry.action && MANIFEST_FRONT == entry.side && entry.output.strip_suffix("b.svg").is_some_and(|stem| entries.contains_key(&format!("{stem}.svg")))
+	}).map(|entry| entry.output.to_string()).collect();
+
+	for output in backs {
+		entries.get_mut(&output).unwrap().side = MANIFEST_BACK.to_string();
+	}
+
+	for (output, backs) in fronts {
+		if MANIFEST_FRONT == entries[&output].side {
+			let back: String = backs.into_iter().find(|back| entries.get(back).is_some_and(|entry| MANIFEST_BACK == entry.side)).unwrap_or_default();
+
+			entries.get_mut(&output).unwrap().back = back;
+		}
+	}
+
+	let mut wtr = match csv::Writer::from_path(&path) {
+		Err(why) => panic!("write_manifest()@{0}: couldn't create manifest '{1}': {2}", line!(), path, why),
+		Ok(wtr) => wtr,
+	};
+
+	for entry in entries.values() {
+		wtr.serialize(entry).unwrap();
+	}
+
+	wtr.flush().unwrap();
+}
+//
+// Writes the manifest once the rows are processed, or when it goes out of scope without being written so that the rows processed
+// before a generator stops (on an error or a panic) are still listed.
+//
+pub struct ManifestWriter<'a> {
+	category: &'static str,
+	args: &'a Arguments,
+	written: bool,
+}
+
+impl<'a> ManifestWriter<'a> {
+	pub fn new(category: &'static str, args: &'a Arguments) -> ManifestWriter<'a> {
+		return ManifestWriter { category, args, written: false };
+	}
+
+	pub fn write(mut self) {
+		write_manifest(self.category, self.args);
+		self.written = true;
+	}
+}
+
+impl Drop for ManifestWriter<'_> {
+	fn drop(&mut self) {
+		if !self.written {
+			write_manifest(self.category, self.args);
+		}
+	}
+}
+//
+// The entries of the nationality groups ("--nationality") and category ("--category") selected, in manifest order.
+//
+pub fn select_manifest_entries<'a>(entries: &'a Vec<ManifestEntry>, selection: &SelectionArguments) -> Vec<&'a ManifestEntry> {
+	let groups: Vec<String> = selection.nationality.split_whitespace().map(|group| group.to_string()).collect();
+	let nationalities: Vec<String> = if groups.is_empty() { Default::default() } else { nationality_codes(&groups) };
+
+	return entries.iter().filter(|entry| {
+		("all" == selection.category || selection.category == entry.category) && (nationalities.is_empty() || nationalities.contains(&entry.nationality))
+	}).collect();
+}
+//
+// The selected entries by nationality and category, it's an error when "--nationality" and "--category" select none.
+//
+pub fn group_manifest_entries<'a>(entries: &'a Vec<ManifestEntry>, outputs: &OutputArguments, selection: &SelectionArguments) -> Result<BTreeMap<(String, String), Vec<&'a ManifestEntry>>, String> {
+	let mut result: BTreeMap<(String, String), Vec<&ManifestEntry>> = Default::default();
+
+	for entry in select_manifest_entries(entries, selection) {
+		result.entry((entry.nationality.to_string(), entry.category.to_string())).or_default().push(entry);
+	}
+
+	if result.is_empty() {
+		return Err(format!("No counters for nationality '{0}' and category '{1}' in '{2}'", selection.nationality, selection.category, manifest_file(&outputs.destination, &outputs.manifest)));
+	}
+
+	return Ok(result);
+}
+//
+// The back of a counter is the manifest's "back" of its front (broken MMC and SMC, malfunctioned guns and SW ...), the back side
+// "<piece>b" in the same folder for manifests without one. Returns the fronts, in the order given, each with its back if it has
+// one, backs aren't listed as fronts (a shared one is paired with each of its fronts).
+//
+pub fn pair_fronts_and_backs<'a>(entries: &Vec<&'a ManifestEntry>) -> Vec<(&'a ManifestEntry, Option<&'a ManifestEntry>)> {
+	let outputs: BTreeMap<&String, &'a ManifestEntry> = entries.iter().map(|entry| (&entry.output, *entry)).collect();
+	let mut result: Vec<(&'a ManifestEntry, Option<&'a ManifestEntry>)> = Default::default();
+	let mut backs: BTreeMap<&String, &'a ManifestEntry> = Default::default();
+
+	for entry in entries {
+		if MANIFEST_BACK == entry.side {
+			continue;
+		}
+
+		let back: String = if !entry.back.is_empty() { entry.back.to_string() } else { format!("{0}b.svg", entry.output.strip_suffix(".svg").unwrap_or(&entry.output)) };
+
+		if let Some(back) = outputs.get(&back).filter(|back| MANIFEST_BACK == back.side) {
+			backs.insert(&entry.output, *back);
+		}
+	}
+
+	let back_outputs: Vec<&String> = backs.values().map(|back| &back.output).collect();
+
+	for entry in entries {
+		if back_outputs.contains(&&entry.output) {
+			continue;
+		}
+
+		result.push((*entry, backs.get(&entry.output).copied()));
+	}
+
+	return result;
+}
+//
+// The legacy GIF a counter replaces, "ge/veh/35sf.gif" for "ge/veh/35sf.svg", relative to the images folder.
+//
+pub fn legacy_image(output: &String) -> String {
+	return Path::new(output).with_extension(LEGACY_EXTENSION).to_string_lossy().to_string();
+}
+//
+// Where the legacy GIF is, in "--legacy-images" (VASL's images folder) or else the destination, None when it isn't there.
+//
+pub fn legacy_image_path(outputs: &OutputArguments, legacy_images: &String, output: &String) -> Option<String> {
+	let folder: String = if legacy_images.is_empty() { outputs.destination.to_string() } else { format!("{0}/", legacy_images.trim_end_matches('/')) };
+	let result: String = format!("{folder}{0}", legacy_image(output));
+
+	return Path::new(&result).is_file().then_some(result);
+}
@@ -0,0 +1,153 @@
+//
+// Local files.
+//
+use crate::colors::*;
+use crate::overrides::*;
+use crate::palettes::*;
+use crate::text_field::*;
+use crate::utils::*;
+//
+// Used when "IF" takes the place of the ROF box, has to fit inside ROF_BOX_SIZE.
+//
+pub const INTENSIVE_FIRE_FONTS: [[f64; 4]; 8] = [
+	[   6.0,   3.6,  72.0,   6.6 ],	// FONT_NORMAL:			[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_UNDERLINED: 	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_OVERLINED:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_BOTHLINED:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_NORMAL:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_UNDERLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_OVERLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_BOTHLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+];
+
+pub const INTENSIVE_FIRE_TEXT: &str =	"IF";	// Replaces the RAMP_DOT in the "intensive_fire" column.
+pub const RADIOLESS_TEXT: &str =		"®";	// Replaces the RAMP_DOT in the "radioless" column.
+pub const CREW_SURVIVAL_PREFIX: &str =	"CS";	// Prepended to the crew survival number.
+
+pub const MARKINGS_X_POSITION: f64 =	3.0;	// Radioless symbol and crew survival number are stacked in the upper left corner.
+pub const MARKINGS_Y_POSITION: f64 =	3.0;
+//
+// Counter-printed markings that are not part of the armament, armor or movement elements.
+//
+#[derive(PartialEq)]
+#[derive(Default)]
+pub struct VehicleMarkings {
+	pub intensive_fire: TextField,
+	pub radioless: TextField,
+	pub crew_survival: TextField,
+}
+
+impl VehicleMarkings {
+	pub fn sanitize(&mut self, intensive_fire: &String, radioless: &String, crew_survival: &String, overrides: &Overrides, colors: &Colors) {
+		self.intensive_fire = sanitize_marking(intensive_fire, INTENSIVE_FIRE_TEXT, INTENSIVE_FIRE_FONTS, colors);
+		self.radioless = sanitize_marking(radioless, RADIOLESS_TEXT, SMALL_TEXT_FONTS, colors);
+		//
+		// Crew survival numbers aren't printed on most counters, so they are only displayed when asked for.
+		//
+		if !overrides.crew_survival.is_empty() {
+			self.crew_survival = sanitize_marking(crew_survival, CREW_SURVIVAL_PREFIX, SMALL_TEXT_FONTS, colors);
+			self.crew_survival.process_overrides(&overrides.crew_survival, PROCESS_ASTERISK_TAGS);
+		}
+		//
+		// Now handle overrides (if any).
+		//
+		if overrides.intensive_fire_ignore {
+			self.intensive_fire = Default::default();
+		} else if !overrides.intensive_fire.is_empty() {
+			self.intensive_fire.process_overrides(&overrides.intensive_fire, PROCESS_ASTERISK_TAGS);
+		}
+
+		if overrides.radioless_ignore {
+			self.radioless = Default::default();
+		} else if !overrides.radioless.is_empty() {
+			self.radioless.process_overrides(&overrides.radioless, PROCESS_ASTERISK_TAGS);
+		}
+
+		for marking in [&mut self.intensive_fire, &mut self.radioless, &mut self.crew_survival] {
+			if marking.text.is_empty() {
+				marking.is_set = false;
+			}
+		}
+	}
+	//
+	// Intensive Fire without an alternate location is shown in the "gun stack", either by underlining the ROF or in place of it.
+	//
+	pub fn intensive_fire_in_gun_stack(&self) -> bool {
+		return self.intensive_fire.is_set && self.intensive_fire.alternate_location.is_empty();
+	}
+	//
+	// Markings without an alternate location are stacked in the upper left corner, the others are relocated (see
+	// generate_relocated_small_text_element()). Intensive Fire is also relocated when there is no "gun stack" to put it in.
+	//
+	pub fn generate_svg_elements(&self, counter_file: &std::fs::File, gun_stack: bool) {
+		let mut y_position: f64 = MARKINGS_Y_POSITION;
+
+		if self.intensive_fire.is_set && (!gun_stack || !self.intensive_fire.alternate_location.is_empty()) {
+			generate_relocated_small_text_element(counter_file, &self.intensive_fire, "Intensive Fire");
+		}
+
+		for (marking, comment) in [(&self.radioless, "Radioless"), (&self.crew_survival, "Crew Survival")] {
+			if !marking.is_set {
+				continue;
+			}
+
+			if marking.alternate_location.is_empty() {
+				y_position += marking.fonts.height();
+				generate_small_text_element(counter_file, marking, &marking.text, comment, MARKINGS_X_POSITION, y_position, "start");
+				y_position += 1.0; // Magic!
+			} else {
+				generate_relocated_small_text_element(counter_file, marking, comment);
+			}
+		}
+	}
+}
+//
+// A RAMP_DOT means the marking applies as is, anything left after it (e.g., "9" or "<sup>75</sup>") is displayed after the marking's text
+// (the crew survival number has no RAMP_DOT, so it simply follows CREW_SURVIVAL_PREFIX).
+// A dagger anywhere in the column adds the asterisk note after the marking.
+//
+fn sanitize_marking(source: &str, text: &str, fonts: [[f64; 4]; 8], colors: &Colors) -> TextField {
+	let mut result: TextField = Default::default();
+	let mut temp: String = source.trim().to_string();
+	let mut limited: bool = false;
+	let mut italic: bool = false;
+
+	result.color = colors.text.to_string();
+	result.fonts.initialize(fonts);
+
+	if temp.is_empty() {
+		return result;
+	}
+
+	if temp.contains(DAGGER) {
+		result.note.action = NoteAction::Postfix;
+		result.note.text = FIVE_LOBED_ASTERISK_SVG.to_string();
+		temp = strip_daggered_note(&temp);
+	}
+
+	if temp.contains("<b>") {
+		limited = true;
+		temp = strip_html_bold(&temp);
+	}
+
+	if temp.contains("<i>") {
+		italic = true;
+		temp = temp.replace("<i>", "").replace("</i>", "");
+	}
+
+	temp = format!("{0}{1}", text, temp.replace(RAMP_DOT, ""));
+	temp = convert_superscripts(&temp, result.fonts.sup_size());
+
+	if italic {
+		temp = format!("<tspan style=\"font-style:italic\">{0}</tspan>", temp);
+	}
+
+	if limited {
+		temp = format!("<tspan style=\"fill:{0}\">{1}</tspan>", palette_color(RED), temp);
+	}
+
+	result.is_set = true;
+	result.text = temp;
+
+	return result;
+}
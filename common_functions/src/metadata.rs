@@ -0,0 +1,107 @@
+use std::io::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+//
+// Local files.
+//
+use crate::utils::*;
+
+pub const METADATA_FORMAT: &str =	"vasl_counter_metadata/1";	// Bump when fields are renamed or removed, adding fields is fine.
+pub const METADATA_ID: &str =		"counter_metadata";
+//
+// Machine-readable description of a counter, embedded in every SVG as JSON inside <metadata id="counter_metadata">.
+// Empty fields are left out. Values are taken from the CSV with any HTML tags removed.
+//
+#[derive(Serialize)]
+#[derive(PartialEq)]
+#[derive(Default)]
+#[derive(Clone)]
+pub struct CounterMetadata {
+	pub format: String,
+	pub piece: String,
+	pub name: String,
+	pub nationality: String,
+	pub unit_type: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub note: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub notes: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub bpv: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub rf: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub dates: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub weight: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub count: String,
+	pub data_version: String,
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub source_row: String,	// The fingerprint of the data file row (see row_fingerprint()).
+	pub generator: String,
+	pub generator_version: String,
+}
+
+impl CounterMetadata {
+	pub fn new(unit_type: &str, nationality: &String) -> CounterMetadata {
+		let mut result: CounterMetadata = Default::default();
+
+		result.unit_type = unit_type.to_string();
+		result.nationality = nationality.to_string();
+
+		return result;
+	}
+	//
+	// One CSV row can produce several counters (alternate pieces, limbered and malfunctioned sides).
+	//
+	pub fn for_piece(&self, piece: &String) -> CounterMetadata {
+		let mut result: CounterMetadata = self.clone();
+
+		result.piece = piece.to_string();
+
+		return result;
+	}
+	//
+	// Chapter H values (the same columns are used by vehicles, ordnance and landing craft).
+	//
+	pub fn set_chapter_h_values(&mut self, notes: &String, bpv: &String, rf: &String, dates: &String) {
+		self.notes = strip_html_tags(notes);
+		self.bpv = strip_html_tags(bpv);
+		self.rf = strip_html_tags(rf);
+		self.dates = strip_html_tags(dates);
+	}
+	//
+	// Used by generate_counter_header_svg_elements(), the name, note, data version and generator come from the header's arguments.
+	//
+	pub fn generate_svg_elements(&self, mut counter_file: &std::fs::File) {
+		let json: String = match serde_json::to_string(&self) {
+			Err(why) => panic!("CounterMetadata::generate_svg_elements()@{0}: couldn't serialize metadata for '{1}': {2}", line!(), self.piece, why),
+			Ok(json) => json,
+		};
+
+		write!(counter_file, "\t<title>{0}</title>\n", escape_xml(&self.name)).unwrap();
+		write!(counter_file, "\t<desc>{0}</desc>\n", escape_xml(&self.description())).unwrap();
+		write!(counter_file, "\t<metadata id=\"{0}\">{1}</metadata>\n\n", METADATA_ID, escape_xml(&json)).unwrap();
+	}
+	//
+	// Human-readable one-liner for <desc>, e.g., "ge vehicle 'PzIIIE', Chapter H note 12, BPV 41, RF 1.3, dates 39-41".
+	//
+	fn description(&self) -> String {
+		let mut result: String = format!("{0} {1} '{2}'", self.nationality, self.unit_type, self.piece);
+
+		for (label, value) in [("Chapter H note", &self.note), ("BPV", &self.bpv), ("RF", &self.rf), ("dates", &self.dates), ("weight", &self.weight), ("count", &self.count)] {
+			if !value.is_empty() {
+				result.push_str(&format!(", {0} {1}", label, value));
+			}
+		}
+
+		return result.trim().to_string();
+	}
+}
+
+pub fn strip_html_tags(source: &str) -> String {
+	let re = Regex::new(r"<[^>]*>").unwrap();
+
+	return re.replace_all(source, "").trim().to_string();
+}
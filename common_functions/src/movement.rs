@@ -9,6 +9,7 @@ use regex::Regex;
 use crate::colors::*;
 use crate::defines::*;
 use crate::overrides::*;
+use crate::palettes::*;
 use crate::text_field::*;
 use crate::utils::*;
 
@@ -214,7 +215,7 @@ impl VehicleMovementValues {
 		self.points.fonts.initialize(MP_FONTS);
 		
 		if my_mps.contains("<b>") {
-			self.points.color = RED.to_string();
+			self.points.color = palette_color(RED);
 			self.points.text.push_str(&strip_html_bold(&my_mps));
 		} else {
 			self.points.color = colors.movement_points_text.to_string();
@@ -378,20 +379,9 @@ impl OrdnanceMovementValues {
 		self.manhandling_number.fonts.initialize(MH_NUMBER_FONTS);
 	
 		if !overrides.target_size.is_empty() {
-			self.target_size = overrides.target_size.parse::<i64>().unwrap_or(0);
+			self.set_target_size(overrides.target_size.parse::<i64>().unwrap_or(0), colors);
 		} else {
-			self.target_size = target_size;
-		}
-	
-		if 0 < self.target_size {
-			self.manhandling_number.color = BLACK.to_string();
-			self.unhooking_penalty_color = colors.unhooking_penalty_color.to_string();
-		} else if 0 == self.target_size {
-			self.manhandling_number.color = colors.manhandling_fill.to_string();
-			self.unhooking_penalty_color = colors.unhooking_penalty_color.to_string();
-		} else {
-			self.manhandling_number.color = RED.to_string();
-			self.unhooking_penalty_color = RED.to_string();
+			self.set_target_size(target_size, colors);
 		}
 	
 		self.manhandling_number.text = manhandling.to_string();
@@ -429,6 +419,53 @@ impl OrdnanceMovementValues {
 		}
 	}
 	
+	//
+	// Target size also decides the manhandling number and unhooking penalty colors.
+	//
+	pub fn set_target_size(&mut self, target_size: i64, colors: &Colors) {
+		self.target_size = target_size;
+	
+		if 0 < self.target_size {
+			self.manhandling_number.color = BLACK.to_string();
+			self.unhooking_penalty_color = colors.unhooking_penalty_color.to_string();
+		} else if 0 == self.target_size {
+			self.manhandling_number.color = colors.manhandling_fill.to_string();
+			self.unhooking_penalty_color = colors.unhooking_penalty_color.to_string();
+		} else {
+			self.manhandling_number.color = palette_color(RED);
+			self.unhooking_penalty_color = palette_color(RED);
+		}
+	}
+	//
+	// Replace the manhandling number after sanitize() (e.g., for a limbered gun). Accepts the same syntax as NOVR_MANHANDLING ("$$" copies
+	// the current number, "[*]" adds the asterisk note and "<b>X</b>" marks the unhooking penalty).
+	//
+	pub fn override_manhandling_number(&mut self, manhandling: &String) {
+		let copied: bool = manhandling.contains(COPY_FIELD);
+	
+		self.manhandling_number.process_overrides(manhandling, LEAVE_ASTERISK_TAGS);
+	
+		if self.manhandling_number.text.contains(FIVE_LOBED_ASTERISK_TAG) || self.manhandling_number.text.contains(FIVE_LOBED_ASTERISK) {
+			self.manhandling_number.note.action = NoteAction::Infix;
+			self.manhandling_number.note.text = FIVE_LOBED_ASTERISK_UC.to_string();
+			self.manhandling_number.text = convert_text(&self.manhandling_number.text, FIVE_LOBED_ASTERISK_TAG, "");
+			self.manhandling_number.text = strip_all_occurances(&self.manhandling_number.text, FIVE_LOBED_ASTERISK);
+		}
+	
+		if self.manhandling_number.text.contains(DAGGER) {
+			self.manhandling_number.text = strip_dagger_and_any_superscript_from_end(&self.manhandling_number.text);
+			self.manhandling_number.note.action = NoteAction::Infix;
+			self.manhandling_number.note.text = FIVE_LOBED_ASTERISK_UC.to_string();
+		}
+	
+		if self.manhandling_number.text.contains("<b>") {
+			self.manhandling_number.text = extract_string(&self.manhandling_number.text, &String::from("</b>"), &String::from("<b>"));
+			self.unhooking_penalty = true;
+		} else if !copied {
+			self.unhooking_penalty = false;
+		}
+	}
+	
 	pub fn set_font_and_color(&mut self, colors: &Colors) {
 		self.manhandling_number.fonts.initialize(MH_NUMBER_FONTS);
 		self.color = colors.manhandling_fill.to_string();
@@ -0,0 +1,147 @@
+use std::fs::File;
+use std::sync::OnceLock;
+use serde::Deserialize;
+
+pub const NATIONALITIES_FILE: &str =		"./data/nationalities.csv";
+pub const NATIONALITY_CATEGORY: &str =		"{category}";	// Replaced in the "folder" column by "veh", "gun" ...
+pub const NATIONALITY_DELIMITER: char =		'@';			// "ge000H|ss000H@ss" builds the second piece as a Waffen-SS counter.
+pub const NATIONALITY_LIST_DELIMITER: char =	'|';			// Separates the "aliases" and "colors" entries.
+pub const SHARED_NATIONALITY: &str =		"sh";			// Aircraft, landing craft and the vehicles any nationality may use.
+pub const NATIONALITY_ROOT_CATEGORIES: [&str; 3] =	[ "", "inf", "sw" ];	// Infantry and SW are written to the nationality's folder itself.
+//
+// One row per nationality code (the folder name under "cached" and the destination):
+//
+//	group:				name used by the data files and run scripts (e.g., "german" for data/german_vehicles.csv, covering "ge" and "ss").
+//	name:				display name.
+//	aliases:			other names accepted wherever a code is (case is ignored).
+//	side:				parent side ("allied", "axis", "communist" or empty).
+//	folder:				output (and cached) folder relative to the destination, "{category}" is dropped for NATIONALITY_ROOT_CATEGORIES.
+//	background, inner_background, text:	counter colors, an empty inner background means a plain counter.
+//	colors:				any other Colors field as "field=value" (see Colors::set_color()).
+//
+#[derive(Debug, Deserialize)]
+#[derive(Clone)]
+#[derive(Default)]
+#[serde(rename_all = "lowercase")]
+pub struct Nationality {
+	pub code: String,
+	pub group: String,
+	pub name: String,
+	pub aliases: String,
+	pub side: String,
+	pub folder: String,
+	pub background: String,
+	pub inner_background: String,
+	pub text: String,
+	pub colors: String,
+}
+
+impl Nationality {
+	pub fn matches(&self, code: &str) -> bool {
+		return code == self.code || self.aliases.split(NATIONALITY_LIST_DELIMITER).any(|alias| !alias.is_empty() && alias.eq_ignore_ascii_case(code));
+	}
+
+	pub fn output_folder(&self, category: &str) -> String {
+		let category: &str = if NATIONALITY_ROOT_CATEGORIES.contains(&category) { "" } else { category };
+
+		return format!("{0}/", self.folder.replace(NATIONALITY_CATEGORY, category).trim_end_matches('/'));
+	}
+}
+
+static NATIONALITIES: OnceLock<Vec<Nationality>> = OnceLock::new();
+//
+// Read once per run, every record looks up its colors here.
+//
+pub fn read_nationalities() -> &'static Vec<Nationality> {
+	return NATIONALITIES.get_or_init(|| {
+		let mut result: Vec<Nationality> = Default::default();
+		let file = match File::open(NATIONALITIES_FILE) {
+			Err(why) => panic!("couldn't open file: {0} {1}", NATIONALITIES_FILE, why),
+			Ok(file) => file,
+		};
+		let mut rdr = csv::Reader::from_reader(file);
+
+		for nationality in rdr.deserialize() {
+			match nationality {
+				Err(why) => panic!("read_nationalities()@{0}: bad entry in {1}: {2}", line!(), NATIONALITIES_FILE, why),
+				Ok(nationality) => result.push(nationality),
+			}
+		}
+
+		result
+	});
+}
+
+pub fn find_nationality(code: &str) -> Option<&'static Nationality> {
+	return read_nationalities().iter().find(|nationality| nationality.matches(code));
+}
+//
+// Used where the data has to name a known nationality (alternate pieces, ordnance piece prefixes).
+//
+pub fn nationality_code(code: &str) -> String {
+	match find_nationality(code) {
+		None => panic!("nationality_code()@{0}: unknown nationality '{1}' (see {2})", line!(), code, NATIONALITIES_FILE),
+		Some(nationality) => return nationality.code.to_string(),
+	}
+}
+//
+// The folder the nationality's counters of a category are written to (and cached in), relative to the destination.
+//
+pub fn nationality_folder(code: &str, category: &str) -> String {
+	match find_nationality(code) {
+		None => panic!("nationality_folder()@{0}: unknown nationality '{1}' (see {2})", line!(), code, NATIONALITIES_FILE),
+		Some(nationality) => return nationality.output_folder(category),
+	}
+}
+//
+// "piece@xx" is the same row built for nationality "xx" (any alias), returns the piece and the nationality's code.
+//
+pub fn split_alternate_nationality(piece: &String) -> (String, String) {
+	let Some((name, code)) = piece.split_once(NATIONALITY_DELIMITER) else {
+		panic!("split_alternate_nationality()@{0}: no nationality in '{1}'", line!(), piece);
+	};
+
+	return (name.to_string(), nationality_code(code));
+}
+//
+// Ordnance pieces start with their nationality's code (e.g., "geAA2020").
+//
+pub fn nationality_from_piece(piece: &String) -> String {
+	let mut result: String = Default::default();
+
+	for nationality in read_nationalities() {
+		if piece.starts_with(&nationality.code) && nationality.code.len() > result.len() {
+			result = nationality.code.to_string();
+		}
+	}
+
+	if result.is_empty() {
+		panic!("nationality_from_piece()@{0}: no nationality code at the start of '{1}' (see {2})", line!(), piece, NATIONALITIES_FILE);
+	}
+
+	return result;
+}
+//
+// The codes of the given groups, codes or aliases (e.g., "german", "fi" or "finland") in NATIONALITIES_FILE order, all grouped codes
+// when none is given.
+//
+pub fn nationality_codes(names: &Vec<String>) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+	let nationalities: &Vec<Nationality> = read_nationalities();
+
+	for name in names {
+		if !nationalities.iter().any(|nationality| *name == nationality.group || nationality.matches(name)) {
+			panic!("nationality_codes()@{0}: unknown nationality '{1}' (see {2})", line!(), name, NATIONALITIES_FILE);
+		}
+	}
+
+	for nationality in nationalities {
+		let selected: bool = if names.is_empty() { !nationality.group.is_empty() } else { names.iter().any(|name| *name == nationality.group || nationality.matches(name)) };
+
+		if selected {
+			result.push(nationality.code.to_string());
+		}
+	}
+
+	return result;
+}
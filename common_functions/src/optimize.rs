@@ -0,0 +1,286 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::prelude::*;
+use std::path::Path;
+use regex::{Captures, Regex};
+//
+// Local files.
+//
+use crate::arguments::*;
+use crate::depictions::*;
+use crate::generate_bevel_svg_elements;
+use crate::inline::*;
+use crate::manifest::*;
+use crate::profiles::*;
+
+pub const SHARED_DEFS_FILE: &str =	"counter_defs.svg";	// Written to the destination with "--shared-defs".
+const SHARED_DEFS_ID: &str =		"bevel";			// The <defs> written by generate_bevel_svg_elements() (or the flat profile's), replaced by SHARED_DEFS_FILE.
+const KEPT_COMMENT: &str =			"Author(s):";		// The credits stay with the counter.
+//
+// Numbers are trimmed in every attribute but these.
+//
+const UNTRIMMED_ATTRIBUTES: [&str; 4] =	[ "id", "href", "xlink:href", DEPICTION_SOURCE_ATTRIBUTE ];
+
+fn strip_comments(text: &String) -> String {
+	return Regex::new(r"(?s)<!--.*?-->").unwrap().replace_all(text, |found: &Captures| {
+		if found[0].contains(KEPT_COMMENT) { found[0].to_string() } else { "".to_string() }
+	}).to_string();
+}
+//
+// "12.00" becomes "12" and "0.50px" becomes "0.5px", text (names, metadata ...) and the "url(...)" references (e.g., to an id
+// such as "blur1.50", see UNTRIMMED_ATTRIBUTES) are left alone.
+//
+fn trim_numbers(text: &String) -> String {
+	let tag = Regex::new(r"<[A-Za-z][^>]*>").unwrap();
+	let attribute = Regex::new(r#"([A-Za-z_][\w:.-]*)="([^"]*)""#).unwrap();
+	let number = Regex::new(r"url\([^)]*\)|(\d+)\.(\d+)").unwrap();
+
+	return tag.replace_all(text, |element: &Captures| {
+		attribute.replace_all(&element[0], |found: &Captures| {
+			if UNTRIMMED_ATTRIBUTES.contains(&&found[1]) {
+				return found[0].to_string();
+			}
+
+			let value: String = number.replace_all(&found[2], |digits: &Captures| {
+				let (Some(whole), Some(fraction)) = (digits.get(1), digits.get(2)) else {
+					return digits[0].to_string();
+				};
+				let fraction: &str = fraction.as_str().trim_end_matches('0');
+
+				if fraction.is_empty() { whole.as_str().to_string() } else { format!("{0}.{fraction}", whole.as_str()) }
+			}).to_string();
+
+			return format!("{0}=\"{value}\"", &found[1]);
+		}).to_string()
+	}).to_string();
+}
+
+fn defs_ids(text: &String) -> BTreeSet<String> {
+	let id = Regex::new(r#"\bid\s*=\s*"([^"]+)""#).unwrap();
+	let mut result: BTreeSet<String> = Default::default();
+
+	for defs in Regex::new(r"(?s)<defs\b.*?</defs>").unwrap().find_iter(text) {
+		for found in id.captures_iter(defs.as_str()) {
+			result.insert(found[1].to_string());
+		}
+	}
+
+	return result;
+}
+//
+// Ids are only unique within a counter, the counter's name keeps them apart when counters are put together (e.g., a sheet).
+//
+fn id_prefix(counter: &String) -> String {
+	let stem: String = Path::new(counter).file_stem().unwrap_or_default().to_string_lossy().chars().map(|c| if c.is_ascii_alphanumeric() || '-' == c { c } else { '_' }).collect();
+
+	if stem.starts_with(|c: char| c.is_ascii_alphabetic()) {
+		return format!("{stem}_");
+	}
+
+	return format!("_{stem}_");
+}
+//
+// generate_svg_start_element() wraps most fields in <svg x y width height style="overflow:visible">. Without percentages inside
+// (which are relative to the wrapper) a <g> moved to x, y draws the same, an empty wrapper (e.g., an empty field) is dropped.
+// Wrappers that hold other <svg> elements are kept.
+//
+fn collapse_svg_wrappers(text: &String) -> String {
+	let root = Regex::new(r#"<svg width="([^"]*)" height="([^"]*)""#).unwrap();
+	let wrapper = Regex::new(r#"<svg x="([^"]*)" y="([^"]*)" width="([^"]*)" height="([^"]*)" style="overflow:visible">"#).unwrap();
+	let (root_width, root_height): (String, String) = match root.captures(text) {
+		Some(found) => (found[1].to_string(), found[2].to_string()),
+		None => Default::default(),
+	};
+	let mut result: String = Default::default();
+	let mut position: usize = 0;
+
+	for found in wrapper.captures_iter(text) {
+		let start = found.get(0).unwrap();
+		let Some(end) = text[start.end()..].find("</svg>") else {
+			continue;
+		};
+		let content: &str = &text[start.end()..start.end() + end];
+
+		if content.contains("<svg") {
+			continue;
+		}
+
+		let (Ok(x), Ok(y)) = (found[1].parse::<f64>(), found[2].parse::<f64>()) else {
+			continue;
+		};
+		let full_size: bool = 0.0 == x && 0.0 == y && root_width == found[3] && root_height == found[4];
+
+		if !content.trim().is_empty() && content.contains('%') && !full_size {
+			continue;
+		}
+
+		result.push_str(&text[position..start.start()]);
+
+		if content.trim().is_empty() {
+			// Nothing to draw.
+		} else if 0.0 == x && 0.0 == y {
+			result.push_str(content);
+		} else {
+			result.push_str(&format!("<g transform=\"translate({0},{1})\">{content}</g>", &found[1], &found[2]));
+		}
+
+		position = start.end() + end + "</svg>".len();
+	}
+
+	result.push_str(&text[position..]);
+
+	return result;
+}
+//
+// With "--shared-defs" the bevel <defs> goes and "url(#highlight_filter)" becomes "url(../../counter_defs.svg#highlight_filter)".
+// Only renderers that follow references to other files draw the bevel then (resvg and most browsers don't, lint_counter_outputs
+// reports them).
+// Renderers that don't follow references to other files (most browsers) draw such counters without the bevel.
+//
+fn refer_to_shared_defs(text: &String, counter: &String, shared_ids: &BTreeSet<String>, args: &Arguments) -> String {
+	let relative: &str = counter.strip_prefix(&args.destination).unwrap_or(counter);
+	let shared_defs: String = format!("{0}{SHARED_DEFS_FILE}", "../".repeat(relative.matches('/').count()));
+	let result: String = Regex::new(&format!(r#"(?s)<defs id="{SHARED_DEFS_ID}">.*?</defs>"#)).unwrap().replace_all(text, "").to_string();
+
+	return Regex::new(r#"url\(\s*(['"]?)#([^)'"]+)(['"]?)\s*\)"#).unwrap().replace_all(&result, |found: &Captures| {
+		if shared_ids.contains(&found[2]) { format!("url({0}{shared_defs}#{1}{2})", &found[1], &found[2], &found[3]) } else { found[0].to_string() }
+	}).to_string();
+}
+
+fn write_shared_defs(args: &Arguments) -> BTreeSet<String> {
+	let path: String = format!("{0}{SHARED_DEFS_FILE}", args.destination);
+	let mut file = match fs::File::create(&path) {
+		Err(why) => panic!("write_shared_defs()@{0}: couldn't create '{1}': {2}", line!(), path, why),
+		Ok(file) => file,
+	};
+
+	write!(file, "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n").unwrap();
+	write!(file, "<svg width=\"0\" height=\"0\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:svg=\"http://www.w3.org/2000/svg\">\n").unwrap();
+
+	if PROFILE_FLAT == render_profile() {
+		generate_flat_bevel_svg_elements(&file);
+	} else {
+		generate_bevel_svg_elements(&file);
+	}
+
+	write!(file, "</svg>\n").unwrap();
+
+	return match fs::read_to_string(&path) {
+		Err(why) => panic!("write_shared_defs()@{0}: couldn't read '{1}': {2}", line!(), path, why),
+		Ok(text) => defs_ids(&text),
+	};
+}
+//
+// Same drawing, fewer bytes: comments (but the credits) and indentation go, numbers lose their trailing zeros, field wrappers
+// collapse and the ids of the <defs> get the counter's name.
+//
+pub fn optimize_counter(counter: &String, shared_ids: &BTreeSet<String>, args: &Arguments) {
+	let mut text: String = match fs::read_to_string(counter) {
+		Err(why) => panic!("optimize_counter()@{0}: couldn't read '{1}': {2}", line!(), counter, why),
+		Ok(text) => text,
+	};
+
+	text = strip_comments(&text);
+
+	if args.shared_defs {
+		text = refer_to_shared_defs(&text, counter, shared_ids, args);
+	}
+
+	text = prefix_ids(&text, &defs_ids(&text), &id_prefix(counter));
+	text = trim_numbers(&text);
+	text = collapse_svg_wrappers(&text);
+	text = text.lines().map(|line| line.trim_start()).filter(|line| !line.is_empty()).collect::<Vec<&str>>().join("\n");
+	text.push('\n');
+
+	if let Err(why) = fs::write(counter, text) {
+		panic!("optimize_counter()@{0}: couldn't write '{1}': {2}", line!(), counter, why);
+	}
+}
+//
+// Called by the generators after inline_generated_counters() (copied counters are left alone so that they still match their
+// cached checksum).
+//
+pub fn optimize_generated_counters(args: &Arguments) {
+	if !args.optimize && !args.shared_defs {
+		return;
+	}
+
+	let shared_ids: BTreeSet<String> = if args.shared_defs { write_shared_defs(args) } else { Default::default() };
+
+	for counter in run_outputs(MANIFEST_GENERATED) {
+		optimize_counter(&counter, &shared_ids, args);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ROOT: &str = r#"<svg width="48" height="48" version="1.1">"#;
+
+	#[test]
+	fn trim_numbers_drops_trailing_zeros() {
+		assert_eq!(trim_numbers(&r#"<rect x="12.00" y="0.50" width="1.25px" height="10"/>"#.to_string()), r#"<rect x="12" y="0.5" width="1.25px" height="10"/>"#);
+		assert_eq!(trim_numbers(&r#"<path d="M 1.50,2.00 L 3.10,4.0"/>"#.to_string()), r#"<path d="M 1.5,2 L 3.1,4"/>"#);
+	}
+
+	#[test]
+	fn trim_numbers_leaves_text_alone() {
+		assert_eq!(trim_numbers(&r#"<text x="1.50">Version 1.00</text>"#.to_string()), r#"<text x="1.5">Version 1.00</text>"#);
+	}
+
+	#[test]
+	fn trim_numbers_leaves_ids_and_references_alone() {
+		let text: String = r##"<filter id="blur1.50"/><use href="#blur1.50" xlink:href="#blur1.50"/><rect x="2.50" style="filter:url(#blur1.50)" clip-path="url('#clip0.10')"/>"##.to_string();
+
+		assert_eq!(trim_numbers(&text), r##"<filter id="blur1.50"/><use href="#blur1.50" xlink:href="#blur1.50"/><rect x="2.5" style="filter:url(#blur1.50)" clip-path="url('#clip0.10')"/>"##);
+		assert_eq!(trim_numbers(&r#"<image data-depiction="ge/svg/ge1.50.svg" width="1.50"/>"#.to_string()), r#"<image data-depiction="ge/svg/ge1.50.svg" width="1.5"/>"#);
+	}
+
+	#[test]
+	fn collapse_svg_wrappers_moves_the_content() {
+		let text: String = format!(r#"{ROOT}<svg x="0" y="0" width="10" height="10" style="overflow:visible"><rect x="1"/></svg><svg x="3.5" y="4" width="10" height="10" style="overflow:visible"><rect x="2"/></svg></svg>"#);
+
+		assert_eq!(collapse_svg_wrappers(&text), format!(r#"{ROOT}<rect x="1"/><g transform="translate(3.5,4)"><rect x="2"/></g></svg>"#));
+	}
+
+	#[test]
+	fn collapse_svg_wrappers_drops_empty_wrappers() {
+		let text: String = format!("{ROOT}<svg x=\"3\" y=\"4\" width=\"10\" height=\"10\" style=\"overflow:visible\">\n\t</svg></svg>");
+
+		assert_eq!(collapse_svg_wrappers(&text), format!("{ROOT}</svg>"));
+	}
+
+	#[test]
+	fn collapse_svg_wrappers_keeps_percentages_relative_to_their_wrapper() {
+		let text: String = format!(r#"{ROOT}<svg x="3" y="4" width="10" height="10" style="overflow:visible"><text x="50%" y="80%">8</text></svg></svg>"#);
+
+		assert_eq!(collapse_svg_wrappers(&text), text);
+		//
+		// A wrapper the size of the counter at 0, 0 is the same viewport.
+		//
+		let text: String = format!(r#"{ROOT}<svg x="0" y="0" width="48" height="48" style="overflow:visible"><text x="50%" y="80%">8</text></svg></svg>"#);
+
+		assert_eq!(collapse_svg_wrappers(&text), format!(r#"{ROOT}<text x="50%" y="80%">8</text></svg>"#));
+	}
+
+	#[test]
+	fn collapse_svg_wrappers_keeps_nested_wrappers() {
+		let text: String = format!(r#"{ROOT}<svg x="1" y="1" width="20" height="20" style="overflow:visible"><svg x="2" y="2" width="5" height="5" style="overflow:visible"><rect x="0"/></svg><rect x="9"/></svg></svg>"#);
+
+		assert_eq!(collapse_svg_wrappers(&text), format!(r#"{ROOT}<svg x="1" y="1" width="20" height="20" style="overflow:visible"><g transform="translate(2,2)"><rect x="0"/></g><rect x="9"/></svg></svg>"#));
+	}
+
+	#[test]
+	fn defs_ids_only_finds_the_defs() {
+		let text: String = r#"<defs id="bevel"><filter id="highlight_filter"/></defs><rect id="Background"/>"#.to_string();
+
+		assert_eq!(defs_ids(&text), BTreeSet::from([ "bevel".to_string(), "highlight_filter".to_string() ]));
+	}
+
+	#[test]
+	fn id_prefix_starts_with_a_letter() {
+		assert_eq!(id_prefix(&"/tmp/ge/veh/geHetzer.svg".to_string()), "geHetzer_");
+		assert_eq!(id_prefix(&"/tmp/MS/8-1 ldr.svg".to_string()), "_8-1_ldr_");
+	}
+}
@@ -9,18 +9,24 @@ use crate::utils::*;
 pub const OVERRIDE_DELIMITER: char =	'|';		// Delimiter to separate multiple overrides.
 
 pub const NOVR_ANNOUNCE: &str =				"announce";			// Announce the line.
+pub const NOVR_SPECIAL: &str =				"special=";			// Replace the ordnance "special" column, e.g., "special=NT, QSU".
+pub const NOVR_SPECIAL_ADDITIONS: &str =	"special+=";		// Append entries to the ordnance "special" column, e.g., "special+=h-d".
 pub const NOVR_SPECIAL_AMMO: &str =			"ammo=";			// Special ammunition values, override values with MOD_TEXT (include '[' and ']'), can also specify alternate location (MOD_LOCATION) and font size (MOD_FONT_SIZE).
+pub const NOVR_BACK_EXTRA_INFO: &str =		"back_extra=";		// SW only, NOVR_EXTRA_INFO for the malfunctioned back generated from the "repair" column.
 pub const NOVR_BACKGROUND_COLOR: &str =		"bkg=";				// Override the counter's background color.
 pub const NOVR_CAPTURED: &str =				"cap=";				// Piece is captured.
 pub const NOVR_COPY: &str =					"copy";
 pub const NOVR_COUNTER_SIZE: &str =			"counter_size=";
+pub const NOVR_CREW_SURVIVAL: &str =		"cs=";				// Show the crew survival number (not displayed by default), COPY_FIELD is replaced by the "cs" column's value.
 pub const NOVR_DISPLAY_NAME: &str =			"display_name";		// Display name (overrides INCLUDE_NAME)
 pub const NOVR_EXTRA_INFO: &str =			"extra=";			// Show extra information on the counter.
 pub const NOVR_FIXED_BMG: &str =			"fixed_bmg";		// Fixed BMG. Following the '@' is the x-axis center of the white circle that should be displayed behind the BMG factor.
 pub const NOVR_GP: &str =					"gp=";	
 pub const NOVR_GT: &str =					"gt=";	
 pub const NOVR_IFE: &str =					"ife=";	
+pub const NOVR_INTENSIVE_FIRE: &str =		"if=";				// Intensive Fire marking, an empty value removes it.
 pub const NOVR_IGNORE: &str =				"ignore";			// Ignore entry (or reverse counter creation).
+pub const NOVR_IMAGE: &str =				"img=";				// Use another piece's depiction, "img=<piece>[@<nationality>]" (e.g., "img=gePzIVH@ge" for the SS or a captured variant).
 pub const NOVR_MA: &str =					"ma=";				// Main armament.
 pub const NOVR_MANHANDLING: &str =			"man=";				// Manhandling.
 pub const NOVR_MA_MOVING_TARGET: &str =		"ma_movt";			// Main armament moving target penalty.
@@ -35,6 +41,7 @@ pub const NOVR_NAME: &str =					"name=";			// Replace name on counter and in SVG
 pub const NOVR_OPACITY: &str =				"opacity=";			// Translucent counter (e.g., xxSnS - small sniper).
 pub const NOVR_PP_NUMBER: &str =			"pp=";				// PP number.
 pub const NOVR_QUALIFIER: &str =			"qual=";	
+pub const NOVR_RADIOLESS: &str =			"radioless=";		// Radioless symbol, an empty value removes it.
 pub const NOVR_RANGE: &str =				"range=";			// Show range value(s), override values with MOD_TEXT (include '[' and ']'), can also specify alternate location (MOD_LOCATION) and font size (MOD_FONT_SIZE).
 pub const NOVR_RANGE2: &str =				"range2=";			// Show alternate range value(s), override values with MOD_TEXT (include '[' and ']'), can also specify alternate location (MOD_LOCATION) and font size (MOD_FONT_SIZE).
 pub const NOVR_ROF: &str =					"rof=";	
@@ -43,6 +50,8 @@ pub const NOVR_SA_MOVING_TARGET: &str =		"sa_movt";			// Secondary armament movi
 pub const NOVR_SB: &str =					"sb=";				// Secondary armament breakdown.
 pub const NOVR_SHIFT_ARMOR: &str =			"shift_armor_down";	// Shift armor values down.
 pub const NOVR_SIZE: &str =					"size=";			// Target size.
+pub const NOVR_SMOKE_DEPLETION: &str =		"s#=";				// Smoke depletion number(s), an empty value removes them.
+pub const NOVR_SMOKE_DISCHARGER: &str =		"sD#=";				// Smoke discharger number(s), an empty value removes them.
 pub const NOVR_STRIPED: &str =				"striped";			// Japanese & Communist Chinese "striped" Squads and Crews.
 pub const NOVR_TA: &str =					"ta=";				// Turret armor modifies (Superior, inferior ...).
 pub const NOVR_TOWING_NUMBER: &str =		"tow=";				// Towing number.
@@ -77,7 +86,14 @@ pub const MOD_LOCATION_BEFORE_TOWING: &str =	"before_tow";	// Display before whe
 pub const MOD_INC_SIZE: &str =	"sz+";	// Positive delta to the normal font size.
 pub const MOD_DEC_SIZE: &str =	"sz-";	// Negative delta to the normal font size.
 
-pub const LIMBERED_NO_FIRE: &str =	"no_fire";
+//
+// Limbered ordnance only (the "limbered" column also accepts NOVR_GT, NOVR_IFE, NOVR_MA, NOVR_MANHANDLING, NOVR_MB, NOVR_MOUNT, NOVR_RANGE, NOVR_ROF and NOVR_SIZE).
+//
+pub const LIMBERED_NO_FIRE: &str =	"no_fire";	// Limbered gun can't fire (no gun line, ROF or breakdown).
+pub const LIMBERED_PIECE: &str =	"piece=";	// Piece name of the limbered counter, COPY_FIELD is replaced by the front piece name (e.g., "$$-l").
+pub const LIMBERED_REPAIR: &str =	"r_x=";		// Repair and disable numbers for the limbered malfunctioned back (e.g., "2/6").
+
+pub const LIMBERED_PIECE_DEFAULT: &str =	"$$-l";	// See LIMBERED_PIECE.
 
 #[derive(PartialEq)]
 #[derive(Default)]
@@ -109,10 +125,12 @@ pub struct Overrides {
 	pub armor_front: String,
 	pub armor_rear: String,
 	pub armor_side: String,
+	pub back_extra_info: String,
 	pub background_color: String,
 	pub captured: String,
 	pub copy: bool,
 	pub counter_size: u32,
+	pub crew_survival: String,
 	pub display_name: bool,
 	pub extra_info: String,
 	pub fixed_bmg: bool,
@@ -120,6 +138,9 @@ pub struct Overrides {
 	pub gt: String,
 	pub ife: String,
 	pub ignore: bool,
+	pub image: String,
+	pub intensive_fire: String,
+	pub intensive_fire_ignore: bool,
 	pub ma: ArmamentOverrides,
 	pub machine_guns: String,
 	pub machine_guns_set: bool,
@@ -133,12 +154,20 @@ pub struct Overrides {
 	pub opacity: f64,
 	pub pp_number: String,
 	pub pp_number_ignore: bool,
+	pub radioless: String,
+	pub radioless_ignore: bool,
 	pub rfnm: bool,
 	pub range_values: String,
 	pub range2_values: String,
 	pub rof: String,
 	pub sa: ArmamentOverrides,
 	pub target_size: String,
+	pub smoke_depletion: String,
+	pub smoke_depletion_ignore: bool,
+	pub smoke_discharger: String,
+	pub smoke_discharger_ignore: bool,
+	pub special: String,
+	pub special_additions: String,
 	pub special_ammo: String,
 	pub towing_number: String,
 	pub turret_armor_modifiers: String,
@@ -146,6 +175,17 @@ pub struct Overrides {
 }
 
 impl Overrides {
+	//
+	// The depiction to look for (see resolve_depiction()), the piece's own unless NOVR_IMAGE says otherwise.
+	//
+	pub fn depiction(&self, piece: &String) -> String {
+		if !self.image.is_empty() {
+			return self.image.to_string();
+		}
+
+		return piece.to_string();
+	}
+
 	pub fn sanitize(&mut self, overrides: &String) {
 		self.opacity = 1.00;
 		
@@ -157,6 +197,14 @@ impl Overrides {
 					self.announce = true;
 				} else if NOVR_SHIFT_ARMOR == entry {
 					self.shift_armor_down = true;
+				} else if entry.contains(NOVR_SPECIAL) { // Checked early, "special" entries are free text that may contain other keys.
+					self.special = extract_from(&entry, NOVR_SPECIAL);
+				} else if entry.starts_with(NOVR_IMAGE) { // Checked early, the piece name may contain other keys.
+					self.image = extract_from(&entry, NOVR_IMAGE);
+				} else if entry.starts_with(NOVR_BACK_EXTRA_INFO) { // Checked before NOVR_EXTRA_INFO, which it contains.
+					self.back_extra_info = extract_from(&entry, NOVR_BACK_EXTRA_INFO);
+				} else if entry.contains(NOVR_SPECIAL_ADDITIONS) {
+					self.special_additions = extract_from(&entry, NOVR_SPECIAL_ADDITIONS);
 				} else if entry.contains(NOVR_ARMOR_FRONT) {
 					self.armor_front = extract_from(&entry, NOVR_ARMOR_FRONT);
 				} else if entry.contains(NOVR_ARMOR_REAR) {
@@ -171,6 +219,8 @@ impl Overrides {
 					self.copy = true;
 				} else if entry.contains(NOVR_COUNTER_SIZE) {
 					self.counter_size = extract_from(&entry, NOVR_COUNTER_SIZE).parse::<u32>().unwrap_or(48);
+				} else if entry.contains(NOVR_CREW_SURVIVAL) {
+					self.crew_survival = extract_from(&entry, NOVR_CREW_SURVIVAL);
 				} else if entry.contains(NOVR_DISPLAY_NAME) {
 					self.display_name = true;
 				} else if entry.contains(NOVR_EXTRA_INFO) {
@@ -185,6 +235,9 @@ impl Overrides {
 					self.ife = entry; // PASS whole entry so we can recognize override to set IFE to "". extract_from(&entry, NOVR_IFE);
 				} else if entry.contains(NOVR_IGNORE) {
 					self.ignore = true;
+				} else if entry.contains(NOVR_INTENSIVE_FIRE) {
+					self.intensive_fire = extract_from(&entry, NOVR_INTENSIVE_FIRE);
+					self.intensive_fire_ignore = self.intensive_fire.is_empty();
 				} else if entry.contains(NOVR_MA) {
 					self.ma.text = extract_from(&entry, NOVR_MA);
 					self.ma.ignore = self.ma.text.is_empty();
@@ -223,6 +276,9 @@ impl Overrides {
 					self.pp_number_ignore = self.pp_number.is_empty();
 				} else if entry.contains(NOVR_QUALIFIER) {
 					self.note_qualifier = extract_from(&entry, NOVR_QUALIFIER);
+				} else if entry.contains(NOVR_RADIOLESS) {
+					self.radioless = extract_from(&entry, NOVR_RADIOLESS);
+					self.radioless_ignore = self.radioless.is_empty();
 				} else if entry.contains(NOVR_RANGE) {
 					self.range_values = extract_from(&entry, NOVR_RANGE);
 				} else if entry.contains(NOVR_RANGE2) {
@@ -239,6 +295,12 @@ impl Overrides {
 					self.sa.moving_target_penalty = true;
 				} else if entry.contains(NOVR_SIZE) {
 					self.target_size = extract_from(&entry, NOVR_SIZE);
+				} else if entry.contains(NOVR_SMOKE_DEPLETION) {
+					self.smoke_depletion = extract_from(&entry, NOVR_SMOKE_DEPLETION);
+					self.smoke_depletion_ignore = self.smoke_depletion.is_empty();
+				} else if entry.contains(NOVR_SMOKE_DISCHARGER) {
+					self.smoke_discharger = extract_from(&entry, NOVR_SMOKE_DISCHARGER);
+					self.smoke_discharger_ignore = self.smoke_discharger.is_empty();
 				} else if entry.contains(NOVR_STRIPED) {
 					self.striped = true;
 				} else if entry.contains(NOVR_SPECIAL_AMMO) {
@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::sync::OnceLock;
+use serde::Deserialize;
+//
+// Local files.
+//
+use crate::colors::*;
+use crate::nationalities::*;
+
+pub const PALETTES_FILE: &str =	"./data/palettes.csv";
+//
+// One row per color change, selected with "--palette <name>" (no palette leaves the colors alone):
+//
+//	palette:		palette name (e.g., "colorblind", "high_contrast", "print").
+//	nationality:	nationality code (or alias) the row applies to, all nationalities when empty.
+//	color:			a Colors field (see Colors::set_color()) or a color value (e.g., "red", "#ff0000") replaced wherever it is used.
+//	value:			the new color.
+//
+// Field rows win over value rows, nationality rows over the others.
+//
+#[derive(Debug, Deserialize)]
+#[derive(Clone)]
+#[serde(rename_all = "lowercase")]
+pub struct PaletteEntry {
+	pub palette: String,
+	pub nationality: String,
+	pub color: String,
+	pub value: String,
+}
+
+static PALETTE: OnceLock<Vec<PaletteEntry>> = OnceLock::new();
+
+fn read_palettes() -> Vec<PaletteEntry> {
+	let mut result: Vec<PaletteEntry> = Default::default();
+	let file = match File::open(PALETTES_FILE) {
+		Err(why) => panic!("couldn't open file: {0} {1}", PALETTES_FILE, why),
+		Ok(file) => file,
+	};
+	let mut rdr = csv::Reader::from_reader(file);
+
+	for entry in rdr.deserialize() {
+		match entry {
+			Err(why) => panic!("read_palettes()@{0}: bad entry in {1}: {2}", line!(), PALETTES_FILE, why),
+			Ok(entry) => result.push(entry),
+		}
+	}
+
+	return result;
+}
+//
+// Called once by each generator before any counter is built, an empty name keeps the default colors.
+//
+pub fn select_palette(name: &String) {
+	let mut entries: Vec<PaletteEntry> = Default::default();
+
+	if !name.is_empty() {
+		entries = read_palettes().into_iter().filter(|entry| *name == entry.palette).collect();
+
+		if entries.is_empty() {
+			panic!("select_palette()@{0}: unknown palette '{1}' (see {2})", line!(), name, PALETTES_FILE);
+		}
+
+		for entry in &entries {
+			if !entry.nationality.is_empty() && find_nationality(&entry.nationality).is_none() {
+				panic!("select_palette()@{0}: unknown nationality '{1}' in palette '{2}' (see {3})", line!(), entry.nationality, name, NATIONALITIES_FILE);
+			}
+		}
+	}
+
+	if PALETTE.set(entries).is_err() {
+		panic!("select_palette()@{0}: palette already selected", line!());
+	}
+}
+
+fn palette_entries() -> &'static Vec<PaletteEntry> {
+	return PALETTE.get_or_init(Default::default);
+}
+//
+// Colors written directly by the generators (e.g., RED for the red MP and malfunction cues) go through here.
+//
+pub fn palette_color(color: &str) -> String {
+	for entry in palette_entries() {
+		if entry.nationality.is_empty() && entry.color.eq_ignore_ascii_case(color) {
+			return entry.value.to_string();
+		}
+	}
+
+	return color.to_string();
+}
+
+fn applies_to(entry: &PaletteEntry, nationality: &String) -> bool {
+	return entry.nationality.is_empty() || find_nationality(nationality).is_some_and(|found| found.matches(&entry.nationality));
+}
+//
+// Value rows first (a nationality row before one for all nationalities), then field rows (all nationalities before the given one).
+//
+pub fn apply_palette(colors: &mut Colors, nationality: &String) {
+	let entries: &Vec<PaletteEntry> = palette_entries();
+
+	if entries.is_empty() {
+		return;
+	}
+
+	for color in colors.color_values() {
+		let replaces = |entry: &&PaletteEntry| entry.color.eq_ignore_ascii_case(color) && applies_to(entry, nationality);
+
+		if let Some(entry) = entries.iter().filter(|entry| !entry.nationality.is_empty()).find(replaces).or_else(|| entries.iter().filter(|entry| entry.nationality.is_empty()).find(replaces)) {
+			*color = entry.value.to_string();
+		}
+	}
+
+	for specific in [false, true] {
+		for entry in entries.iter().filter(|entry| specific != entry.nationality.is_empty() && applies_to(entry, nationality)) {
+			colors.try_set_color(&entry.color, &entry.value);
+		}
+	}
+}
@@ -0,0 +1,73 @@
+use std::io::prelude::*;
+use std::sync::OnceLock;
+
+pub const PROFILE_BEVEL: &str =	"bevel";	// The default, blurred highlight and shadow (see generate_bevel_svg_elements()).
+pub const PROFILE_FLAT: &str =	"flat";		// Gradient highlight and shadow, no filters, for boards with hundreds of counters.
+//
+// The flat bevel's bands, as a percentage of the counter's size (in absolute coordinates, percentages aren't verified in VASL).
+// About the width of the blurred bevel at 100% zoom.
+//
+const FLAT_BEVEL_WIDTH: f64 =			4.0;
+const FLAT_HIGHLIGHT_OPACITY: f64 =		0.60;
+const FLAT_SHADOW_OPACITY: f64 =		0.45;
+
+static RENDER_PROFILE: OnceLock<String> = OnceLock::new();
+//
+// Called once by each generator before any counter is built, an empty name keeps the bevel.
+//
+pub fn select_render_profile(name: &String) {
+	let profile: String = if name.is_empty() { PROFILE_BEVEL.to_string() } else { name.to_string() };
+
+	if PROFILE_BEVEL != profile && PROFILE_FLAT != profile {
+		panic!("select_render_profile()@{0}: unknown profile '{1}' (use '{2}' or '{3}')", line!(), name, PROFILE_BEVEL, PROFILE_FLAT);
+	}
+
+	if RENDER_PROFILE.set(profile).is_err() {
+		panic!("select_render_profile()@{0}: profile already selected", line!());
+	}
+}
+
+pub fn render_profile() -> &'static String {
+	return RENDER_PROFILE.get_or_init(|| PROFILE_BEVEL.to_string());
+}
+//
+// Same ids as the blurred bevel's <defs> (the clipping is shared), the filters are replaced by one gradient per edge fading inwards.
+//
+pub fn generate_flat_bevel_svg_elements(mut counter_file: &std::fs::File) {
+	let gradients: [(&str, &str, f64, [u32; 4]); 4] = [
+		( "highlight_top",		"#ffffff",	FLAT_HIGHLIGHT_OPACITY,	[ 0, 0, 0, 1 ] ),
+		( "highlight_left",		"#ffffff",	FLAT_HIGHLIGHT_OPACITY,	[ 0, 0, 1, 0 ] ),
+		( "shadow_bottom",		"#000000",	FLAT_SHADOW_OPACITY,	[ 0, 1, 0, 0 ] ),
+		( "shadow_right",		"#000000",	FLAT_SHADOW_OPACITY,	[ 1, 0, 0, 0 ] ),
+	];
+
+	write!(counter_file, "\t<!-- The flat bevel, no filters -->\n").unwrap();
+	write!(counter_file, "\t<defs id=\"bevel\">\n").unwrap();
+
+	for (id, color, opacity, [x1, y1, x2, y2]) in gradients {
+		write!(counter_file, "\t\t<linearGradient id=\"{id}\" x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\">\n").unwrap();
+		write!(counter_file, "\t\t\t<stop offset=\"0\" style=\"stop-color:{color};stop-opacity:{opacity:.2}\"/>\n").unwrap();
+		write!(counter_file, "\t\t\t<stop offset=\"1\" style=\"stop-color:{color};stop-opacity:0\"/>\n").unwrap();
+		write!(counter_file, "\t\t</linearGradient>\n").unwrap();
+	}
+
+	write!(counter_file, "\t\t<clipPath id=\"counter_clipping\">\n").unwrap();
+	write!(counter_file, "\t\t\t<rect x=\"0\" y=\"0\" width=\"100%\" height=\"100%\" ry=\"4\" rx=\"4\" style=\"display:inline;fill:red;fill-opacity:1;stroke:none;stroke-width:0;stroke-dasharray:none;stroke-dashoffset:0;stroke-opacity:0\"/>\n").unwrap();
+	write!(counter_file, "\t\t</clipPath>\n").unwrap();
+	write!(counter_file, "\t</defs>\n").unwrap();
+}
+
+//
+// The size is the counter's in the coordinates it's drawn in (48 for the 24 x 24 counters, see generate_counter_background_svg()).
+//
+pub fn generate_flat_bevel_svg(mut counter_file: &std::fs::File, size: f64) {
+	let width: f64 = size * FLAT_BEVEL_WIDTH / 100.0;
+	let far: f64 = size - width;
+
+	write!(counter_file, "\t\t<g id=\"bevel\" clip-path=\"url(#counter_clipping)\">\n").unwrap();
+	write!(counter_file, "\t\t\t<rect x=\"0\" y=\"{far:.2}\" width=\"{size:.2}\" height=\"{width:.2}\" style=\"fill:url(#shadow_bottom);stroke:none\"/>\n").unwrap();
+	write!(counter_file, "\t\t\t<rect x=\"{far:.2}\" y=\"0\" width=\"{width:.2}\" height=\"{size:.2}\" style=\"fill:url(#shadow_right);stroke:none\"/>\n").unwrap();
+	write!(counter_file, "\t\t\t<rect x=\"0\" y=\"0\" width=\"{size:.2}\" height=\"{width:.2}\" style=\"fill:url(#highlight_top);stroke:none\"/>\n").unwrap();
+	write!(counter_file, "\t\t\t<rect x=\"0\" y=\"0\" width=\"{width:.2}\" height=\"{size:.2}\" style=\"fill:url(#highlight_left);stroke:none\"/>\n").unwrap();
+	write!(counter_file, "\t\t</g>\n").unwrap();
+}
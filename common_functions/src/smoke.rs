@@ -0,0 +1,126 @@
+//
+// Local files.
+//
+use crate::colors::*;
+use crate::defines::*;
+use crate::overrides::*;
+use crate::palettes::*;
+use crate::text_field::*;
+use crate::utils::*;
+
+pub const SMOKE_DEPLETION_PREFIX: &str =	"s";	// Prepended to a bare depletion number (e.g., "8" -> "s8"), "WP6", "sM8" etc. are displayed as is.
+pub const SMOKE_DISCHARGER_PREFIX: &str =	"sD";	// Prepended to a bare discharger number (e.g., "6" -> "sD6").
+pub const SMOKE_VALUE_DELIMITER: char =		';';	// Separates multiple values in one column (e.g., "8; WP6").
+
+#[derive(PartialEq)]
+#[derive(Default)]
+pub struct SmokeValues {
+	pub depletion: TextField,
+	pub discharger: TextField,
+}
+
+impl SmokeValues {
+	pub fn sanitize(&mut self, depletion: &String, discharger: &String, overrides: &Overrides, colors: &Colors) {
+		self.depletion = sanitize_smoke_value(depletion, SMOKE_DEPLETION_PREFIX, colors);
+		self.discharger = sanitize_smoke_value(discharger, SMOKE_DISCHARGER_PREFIX, colors);
+		//
+		// Now handle overrides (if any).
+		//
+		if overrides.smoke_depletion_ignore {
+			self.depletion.is_set = false;
+			self.depletion.text = "".to_string();
+		} else if !overrides.smoke_depletion.is_empty() {
+			self.depletion.process_overrides(&overrides.smoke_depletion, PROCESS_ASTERISK_TAGS);
+		}
+
+		if overrides.smoke_discharger_ignore {
+			self.discharger.is_set = false;
+			self.discharger.text = "".to_string();
+		} else if !overrides.smoke_discharger.is_empty() {
+			self.discharger.process_overrides(&overrides.smoke_discharger, PROCESS_ASTERISK_TAGS);
+		}
+	}
+}
+//
+// Each ';'-separated value may carry its own superscript (e.g., "<sup>J4+</sup>") and be bold (displayed in red, limited availability).
+// A dagger anywhere in the column adds the asterisk note after the whole field.
+//
+fn sanitize_smoke_value(source: &str, prefix: &str, colors: &Colors) -> TextField {
+	let mut result: TextField = Default::default();
+	let mut values: Vec<String> = Default::default();
+
+	result.color = colors.text.to_string();
+	result.fonts.initialize(SMALL_TEXT_FONTS);
+
+	for value in source.split(SMOKE_VALUE_DELIMITER) {
+		let mut temp: String = value.trim().to_string();
+		let mut limited: bool = false;
+
+		if temp.contains(DAGGER) {
+			result.note.action = NoteAction::Postfix;
+			result.note.text = FIVE_LOBED_ASTERISK_SVG.to_string();
+			temp = strip_daggered_note(&temp);
+		}
+
+		if temp.contains("<b>") {
+			limited = true;
+			temp = strip_html_bold(&temp);
+		}
+
+		if temp.is_empty() {
+			continue;
+		}
+
+		if temp.starts_with(|c: char| c.is_ascii_digit()) {
+			temp = format!("{0}{1}", prefix, temp);
+		}
+
+		temp = convert_superscripts(&temp, TRANSPORT_SUPERSCRIPT_FONT_SIZE);
+
+		if limited {
+			temp = format!("<tspan style=\"fill:{0}\">{1}</tspan>", palette_color(RED), temp);
+		}
+
+		values.push(temp);
+	}
+
+	if !values.is_empty() {
+		result.is_set = true;
+		result.text = values.join(" ");
+	} else {
+		result.note.action = NoteAction::None;
+	}
+
+	return result;
+}
+//
+// Smoke values without an alternate location share one line in the "gun stack", the others are relocated (see
+// generate_relocated_small_text_element()).
+//
+pub fn generate_smoke_elements(counter_file: &std::fs::File, smoke: &SmokeValues, gun_stack: bool, y_position: f64) -> f64 {
+	let mut result: f64 = 0.0;
+	let mut line: Vec<&TextField> = Default::default();
+
+	for value in [&smoke.depletion, &smoke.discharger] {
+		if value.text.is_empty() {
+			continue;
+		}
+
+		if MOD_LOCATION_MGS == value.alternate_location || MOD_LOCATION_ABOVE_MGS == value.alternate_location || !gun_stack {
+			generate_relocated_small_text_element(counter_file, value, "Smoke");
+		} else {
+			line.push(value);
+		}
+	}
+
+	if !line.is_empty() {
+		let text: Vec<String> = line.iter().map(|value| format!("{0}{1}", value.text, if NoteAction::Postfix == value.note.action { value.note.text.to_string() } else { "".to_string() })).collect();
+		let mut first: TextField = line[0].clone();
+
+		first.note.action = NoteAction::None;
+		generate_small_text_element(counter_file, &first, &text.join(" "), "Smoke", GUN_COLUMN_X_POSITION, y_position, "start");
+		result = first.fonts.height() + gun_column_y_gap(counter_file, GUN_COLUMN_X_POSITION, y_position - first.fonts.height(), "gray");
+	}
+
+	return result;
+}
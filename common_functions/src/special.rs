@@ -1,18 +1,53 @@
-/* TODO: CREATE_WRECKS/CREATE_MALF_SIDE NOT YET?
-use std::fmt;
+use std::{error::Error, fmt};
+use std::collections::BTreeSet;
 use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+use regex::Regex;
 //
 // Local files.
 //
-use crate::defines::*;
 use crate::colors::*;
+use crate::manifest::*;
 use crate::overrides::*;
-use crate::structs::*;
-use crate::turret::*;
+use crate::text_field::*;
 use crate::utils::*;
 
+pub const SPECIAL_FONTS: [[f64; 4]; 8] = [
+	[   4.8,   3.6,   0.0,   4.8 ],	// FONT_NORMAL:			[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_UNDERLINED: 	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_OVERLINED:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_BOTHLINED:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_NORMAL:		[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_UNDERLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_OVERLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+	[   0.0,   0.0,   0.0,   0.0 ],	// FONT_ALT_BOTHLINED:	[ FA_SIZE, FA_SUP_SIZE, FA_Y_PERCENTAGE, FA_HEIGHT ]
+];
+
+pub const SPECIAL_FONT_SIZE_MEDIUM: f64 =	6.4;
+pub const SPECIAL_FONT_SIZE_LARGE: f64 =	8.0;
+pub const SPECIAL_LIMBERED_FONT_SIZE: f64 =	7.0;
+
+pub const SPECIAL_LEFT_X_POSITION: f64 =	 3.0;
+pub const SPECIAL_MIDDLE_X_POSITION: f64 =	30.0;
+pub const SPECIAL_RIGHT_X_POSITION: f64 =	57.0;
+pub const SPECIAL_TOP_Y_POSITION: f64 =		 1.0;	// Upper lines are placed below this, e.g., a 7px "Limbered" has its baseline at 8.0.
+pub const SPECIAL_BOTTOM_Y_POSITION: f64 =	57.0;	// Baseline of the bottom-most lower line.
+pub const SPECIAL_LINE_GAP: f64 =			 0.6;
+pub const SPECIAL_MAX_LINES: usize =		 2;		// Per position.
+
+pub const SPECIAL_DELIMITER: char =			',';
+pub const SPECIAL_LIMBERED_FIRE: &str =		"LF";	// "LF [...]" lists the limbered fire values, the "limbered" column builds the limbered counter from them.
+pub const SPECIAL_MOUNTS: [&str; 6] =		[ "NT", "T", "ST", "RST", "1MT", "SL" ];
+//
+// Entries that aren't displayed: the ammunition ("s8", "WP7", "H6[9]", "A4<sup>2</sup>/5<sup>3</sup>", "IR" ..., the "ammo="
+// override shows it) and the rules notes the counter doesn't carry ("Acq. NA", "Low Ammo", "4 TK DR", "“12” AP TK#" ...).
+//
+const SPECIAL_NOT_DISPLAYED: &str =	r"^(((s|S|WP|H|HE|A|AP|C|D)\d*(\[\d+\])?|IR)(<sup>[^<]*</sup>)?(/\d+(<sup>[^<]*</sup>)?)*|Acq\.? NA|Low Ammo|\d+ ?TK DR|(“\d+” )?AP TK#\d*|uses APCR TK Table|Unhooking risk)$";
+
 #[derive(PartialEq)]
 #[derive(Default)]
+#[derive(Clone)]
+#[derive(Copy)]
 pub enum SpecialPositions {
 	#[default]
 	None,
@@ -22,6 +57,21 @@ pub enum SpecialPositions {
 	UpperMiddle,
 }
 
+impl SpecialPositions {
+	//
+	// The position a line moves on to when this one is full, back to LowerLeft after UpperMiddle.
+	//
+	fn next(&self) -> SpecialPositions {
+		return match self {
+			SpecialPositions::None => SpecialPositions::None,
+			SpecialPositions::LowerLeft => SpecialPositions::UpperRight,
+			SpecialPositions::UpperRight => SpecialPositions::LowerMiddle,
+			SpecialPositions::LowerMiddle => SpecialPositions::UpperMiddle,
+			SpecialPositions::UpperMiddle => SpecialPositions::LowerLeft,
+		};
+	}
+}
+
 impl fmt::Display for SpecialPositions {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -33,265 +83,314 @@ impl fmt::Display for SpecialPositions {
         }
     }
 }
-
+//
+// Entries that weren't recognized or didn't fit on a counter, reported once the generator is done (see check_special_errors()).
+//
+static SPECIAL_ERRORS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+static NOT_DISPLAYED: OnceLock<Regex> = OnceLock::new();
+static TRAILING_SUPERSCRIPT: OnceLock<Regex> = OnceLock::new();
+//
+// The Chapter H "special" column of an ordnance piece. The mount ("NT", "ST" ...) can be any entry, the others are either
+// displayed at one of the SpecialPositions or, like the repair number, feed other elements.
+//
 #[derive(PartialEq)]
 #[derive(Default)]
 #[derive(Clone)]
 pub struct Special {
-	pub turret_type: TurretType,
+	pub mount: String,	// "NT", "ST" ... (see sanitize_mount()), empty when the column doesn't have one.
 	pub ll_lines: Vec<TextField>,	// Lower left lines
-	pub ll_count: usize,
 	pub ur_lines: Vec<TextField>,	// Upper right lines
-	pub ur_count: usize,
 	pub lm_lines: Vec<TextField>,	// Lower middle lines
-	pub lm_count: usize,
 	pub um_lines: Vec<TextField>,	// Upper middle lines
-	pub um_count: usize,
 	pub repair: String,
+	pub limbered_fire: String,
 }
 
 impl Special {
-	pub fn initialize(&mut self, source: &String, overrides: &Overrides, limbered: bool, colors: &Colors) {
-		let mut entries: Vec<std::string::String> = Default::default();
-		let _limber_fire: String;
+	pub fn initialize(&mut self, source: &String, overrides: &Overrides, colors: &Colors) {
 		let mut my_source = source.to_string();
-		
-		if source.contains("LF") {
-			_limber_fire = extract_string(source, "]", "[");
-			my_source = remove_string(source, ", LF", "]");
-		}
-		
+
 		if !overrides.special.is_empty() {
 			my_source = overrides.special.clone();
 		}
-		
+
 		if !overrides.special_additions.is_empty() {
-			my_source.push_str(",");
+			my_source.push(SPECIAL_DELIMITER);
 			my_source.push_str(&overrides.special_additions);
 		}
-		
-		if my_source.contains(COMMA) {
-			entries = extract_vector(&my_source, COMMA);
-		} else {
-			entries.push(my_source.to_string());
-		}
-		
-		if limbered {
-			let mut tf: TextField = Default::default();
-			
-			tf.text = "Limbered".to_string();
-			tf.font_size = 106;
-			tf.color = colors.text.to_string();
-			
-			self.ur_lines.push(tf);
-		}
-		
+
+		let entries: Vec<String> = split_special_entries(&my_source);
+
 		for (position, entry) in entries.iter().enumerate() {
-			if !entry.is_empty() {
-				if 0 == position {
-					self.turret_type = string_to_turret_type(&entry);
-				} else {
-					self.process_entry(&entry, colors);
-				}
+			//
+			// An entry that's already there (e.g., the column's text repeated on a second line) isn't displayed twice.
+			//
+			if entry.is_empty() || entries[..position].contains(entry) {
+				continue;
+			}
+
+			if entry.starts_with(SPECIAL_LIMBERED_FIRE) {
+				self.limbered_fire = extract_string(entry, "]", "[");
+			} else if is_mount(entry) {
+				self.mount = strip_dagger_and_any_superscript_from_end(entry).trim().to_string();
+			} else {
+				self.process_entry(entry, colors);
+			}
+		}
+	}
+	//
+	// "Limbered" always comes first in the upper right, when it's full its last line moves on to the next position.
+	//
+	pub fn set_limbered(&mut self, color: &String) {
+		let mut tf: TextField = new_special_line(&"Limbered".to_string(), SPECIAL_LIMBERED_FONT_SIZE);
+
+		tf.color = color.to_string();
+		self.ur_lines.insert(0, tf);
+
+		if SPECIAL_MAX_LINES < self.ur_lines.len() {
+			if let Some(line) = self.ur_lines.pop() {
+				self.insert(&line, SpecialPositions::UpperRight.next());
 			}
 		}
 	}
-	
+
 	fn process_entry(&mut self, entry: &String, colors: &Colors) {
-		let mut tf: TextField = Default::default();
+		let mut text: String = strip_dagger_and_any_superscript_from_end(entry).trim().to_string();
+		let mut font_size: f64 = SPECIAL_FONTS[FONT_NORMAL][FA_SIZE];
 		let mut sp: SpecialPositions = Default::default();
 
-		tf.text = strip_dagger_and_any_superscript_from_end(entry);
-		tf.font_size = 80;
+		if is_portage_points(&text) {
+			font_size = SPECIAL_FONT_SIZE_MEDIUM;
+			sp = SpecialPositions::LowerLeft;
+		} else {
+			match text.as_str() {
+				"QSU" => {
+					font_size = SPECIAL_FONT_SIZE_LARGE;
+					sp = SpecialPositions::LowerLeft;
+				}
+				"NM" => {
+					text = format!("{0}NM", FIVE_LOBED_ASTERISK_SVG);
+					font_size = SPECIAL_FONT_SIZE_LARGE;
+					sp = SpecialPositions::LowerLeft;
+				}
+				"RFNM" => {
+					self.insert(&new_special_line(&" ".to_string(), SPECIAL_FONT_SIZE_LARGE), SpecialPositions::LowerMiddle); // "*RFNM" will take up too much space, reserve the bottom-most "LowerMiddle" text area.
 
-		match tf.text.as_str() { 
-			"QSU" => {
-				tf.font_size = 133;
-				sp = SpecialPositions::LowerLeft;
-			}
-			"NM" => {
-				tf.text = "*NM".to_string();
-				tf.font_size = 133;
-				sp = SpecialPositions::LowerLeft;
-			}
-			"RFNM" => {
-				tf.font_size = 133;
-
-				tf.text = " ".to_string();				// "*RFNM" will take up too much space, reserve the bottom-most "LowerMiddle" text area.
-				sp = SpecialPositions::LowerMiddle;
-				self.insert(&tf, sp);
-				
-				tf.text = "*RFNM".to_string();
-				sp = SpecialPositions::LowerLeft;
-			}			
-			"h-d" | "no IF" | "Towed" => {
-				tf.font_size = 106;
-				sp = SpecialPositions::LowerMiddle;
-			}
-			"IFE=B10" => {
-				tf.text = "*IFE=B10".to_string();
-				sp = SpecialPositions::LowerMiddle;
-			}	
-			"Towing risk" => {
-				sp = SpecialPositions::LowerMiddle;
-			}			
-			"no shield" | "no gunshield" | "no Gunshield" => {
-				tf.text = "no shield".to_string();
-				sp = SpecialPositions::UpperMiddle;
-			}
-			"Towing NA" => {
-				tf.text = SIX_LOBED_ASTERISK_SVG_SUFFIX.to_string();
-				tf.text.push_str("Tow NA");
-				sp = SpecialPositions::LowerMiddle;
-			}
-			"Road MP = 1" => {
-				tf.text = SIX_LOBED_ASTERISK_SVG_SUFFIX.to_string();
-				tf.text.push_str("Road MP=1");
-				sp = SpecialPositions::UpperMiddle;
-			}
-			"black_th" => {
-				tf.text = "*Black TH#".to_string();
-				sp = SpecialPositions::UpperMiddle;
-			}
-			"H=B12" => {
-				tf.text = "*H=B12".to_string();
-				sp = SpecialPositions::LowerMiddle;
-			}
-			"IR<sup>3+</sup>" => {
-				tf.text = convert_superscripts(&tf.text, 60);
-				sp = SpecialPositions::LowerLeft;
-			}
-			"[3-24]<sup>3+</sup>" => {
-				tf.text = convert_superscripts(&tf.text, 60);
-				sp = SpecialPositions::LowerMiddle;				
-			}
-			"s8<sup>N1+</sup>" => {
-				tf.text = convert_superscripts(&"*s8<sup>N1+</sup>".to_string(), 60);
-				sp = SpecialPositions::LowerMiddle;				
-			}
-			"AA Fire ROF 2" => {
-				tf.text = convert_superscripts(&"*AA ROF 2".to_string(), 60);
-				sp = SpecialPositions::UpperMiddle;				
-			}
-			"2 TK DR" => {
-				tf.text = "*2 TK DR".to_string();
-				sp = SpecialPositions::LowerMiddle;				
-			}
-			"R2" => {
-				self.repair = "2".to_string();
-			}
-			_ => {
-				// Don't recognize it, so skip it.
+					text = format!("{0}RFNM", FIVE_LOBED_ASTERISK_SVG);
+					font_size = SPECIAL_FONT_SIZE_LARGE;
+					sp = SpecialPositions::LowerLeft;
+				}
+				"h-d" | "no IF" | "Towed" | "Fire Lane" => {
+					font_size = SPECIAL_FONT_SIZE_MEDIUM;
+					sp = SpecialPositions::LowerMiddle;
+				}
+				"IFE=B10" | "H=B12" | "2 TK DR" => {
+					text = format!("{0}{1}", FIVE_LOBED_ASTERISK_SVG, text);
+					sp = SpecialPositions::LowerMiddle;
+				}
+				"Towing risk" => {
+					sp = SpecialPositions::LowerMiddle;
+				}
+				"no shield" | "no gunshield" | "no Gunshield" | "No Gunshield" => {
+					text = "no shield".to_string();
+					sp = SpecialPositions::UpperMiddle;
+				}
+				"Towing NA" => {
+					text = format!("{0}Tow NA", SIX_LOBED_ASTERISK_SVG);
+					sp = SpecialPositions::LowerMiddle;
+				}
+				"Road MP = 1" => {
+					text = format!("{0}Road MP=1", SIX_LOBED_ASTERISK_SVG);
+					sp = SpecialPositions::UpperMiddle;
+				}
+				"black_th" | "Blk TH" => {
+					text = format!("{0}Black TH#", FIVE_LOBED_ASTERISK_SVG);
+					sp = SpecialPositions::UpperMiddle;
+				}
+				"IR<sup>3+</sup>" => {
+					sp = SpecialPositions::LowerLeft;
+				}
+				"[3-24]<sup>3+</sup>" | "s8<sup>N1+</sup>" => {
+					sp = SpecialPositions::LowerMiddle;
+				}
+				"AA Fire ROF 2" => {
+					text = format!("{0}AA ROF 2", FIVE_LOBED_ASTERISK_SVG);
+					sp = SpecialPositions::UpperMiddle;
+				}
+				"R2" => {
+					self.repair = "2".to_string();
+				}
+				_ => {
+					let plain: String = TRAILING_SUPERSCRIPT.get_or_init(|| Regex::new(r"<sup>[^<]*</sup>$").unwrap()).replace(&text, "").to_string();
+
+					if plain != text {
+						self.process_entry(&plain, colors);	// "Towed<sup>A1+</sup>" is placed as "Towed", the superscript refers to a note.
+					} else if !text.is_empty() && !NOT_DISPLAYED.get_or_init(|| Regex::new(SPECIAL_NOT_DISPLAYED).unwrap()).is_match(&text) {
+						SPECIAL_ERRORS.lock().unwrap().insert(format!("line {0}: unknown special entry '{1}'", manifest_source_line(), entry));
+					}
+				}
 			}
 		}
 
 		if SpecialPositions::None != sp {
+			let mut tf: TextField = new_special_line(&text, font_size);
+
 			tf.color = colors.text.to_string();
 			self.insert(&tf, sp);
 		}
 	}
-
+	//
+	// Each position holds SPECIAL_MAX_LINES, when full the line moves on to the next free one. A line that doesn't fit anywhere is
+	// left out and reported as a data error.
+	//
 	pub fn insert(&mut self, tf: &TextField, sp: SpecialPositions) {
-		let mut inserted = false;
 		let mut my_sp = sp;
-		
-		while SpecialPositions::None != my_sp && !inserted {
-			match my_sp {
-				SpecialPositions::LowerLeft => {
-					if 2 == self.ll_count {
-						my_sp = SpecialPositions::UpperRight;
-					} else {
-						self.ll_lines.push(tf.clone());
-						inserted = true;
-					}
-				}
-				SpecialPositions::UpperRight => {
-					if 2 == self.ur_count {
-						my_sp = SpecialPositions::LowerMiddle;
-					} else {
-						self.ur_lines.push(tf.clone());
-						inserted = true;
-					}
-				}
-				SpecialPositions::LowerMiddle => {
-					if 2 == self.lm_count {
-						my_sp = SpecialPositions::UpperMiddle;
-					} else {
-						self.lm_lines.push(tf.clone());
-						inserted = true;
-					}					
-				}
-				SpecialPositions::UpperMiddle => {
-					if 2 == self.um_count {
-						my_sp = SpecialPositions::None;	// No room at the Inn, nothing we can do with this one, hopefully never happens.
-						println!("Cannot add special text.");
-					} else {
-						self.um_lines.push(tf.clone());
-						inserted = true;
-					}					
-				}
-				_ => {
-					// We shouldn't get here.
-				}
+
+		for _ in 0..4 {
+			let lines: &mut Vec<TextField> = match my_sp {
+				SpecialPositions::LowerLeft => &mut self.ll_lines,
+				SpecialPositions::UpperRight => &mut self.ur_lines,
+				SpecialPositions::LowerMiddle => &mut self.lm_lines,
+				SpecialPositions::UpperMiddle => &mut self.um_lines,
+				SpecialPositions::None => break,
+			};
+
+			if SPECIAL_MAX_LINES > lines.len() {
+				lines.push(tf.clone());
+				return;
 			}
-		}		
+
+			my_sp = my_sp.next();
+		}
+
+		SPECIAL_ERRORS.lock().unwrap().insert(format!("line {0}: no room for special text '{1}' (starting at {sp})", manifest_source_line(), tf.text));
 	}
-	
-	pub fn find(&self, source: &String) -> TextField  {
+
+	pub fn find(&self, source: &String) -> TextField {
 		let mut result: TextField = Default::default();
-		let mut found = false;
-		
-		for line in &self.ll_lines {
-			if source.to_string() == line.text.to_string() {
+
+		for line in self.ll_lines.iter().chain(self.ur_lines.iter()).chain(self.lm_lines.iter()).chain(self.um_lines.iter()) {
+			if *source == line.text {
 				result = line.clone();
-				found = true;
+				break;
 			}
 		}
-		
-		if !found {
-			for line in &self.ur_lines {
-				if source.to_string() == line.text.to_string() {
-					result = line.clone();
-				}
-			}
-		}
-		
+
 		return result;
 	}
-	
-	pub fn generate_svg(&self, mut counter_file: &std::fs::File) {
-		let mut x_pos: u32 = 50;
-		let mut y_pos: u32 = 950;
-		
-		for line in &self.ll_lines {
-			write!(counter_file, "\t\t<text x=\"{0}\" y=\"{1}\" text-anchor=\"start\" style=\"font-size:{2}px;font-weight:normal;font-family:{3};fill:{4}\">{5}</text>\n", x_pos, y_pos, line.font_size, FONT_MAIN, line.color, line.text).unwrap();				
-			y_pos -= line.font_size;
+
+	pub fn is_empty(&self) -> bool {
+		return self.ll_lines.is_empty() && self.ur_lines.is_empty() && self.lm_lines.is_empty() && self.um_lines.is_empty();
+	}
+	//
+	// The malfunctioned back has room for all four positions. On the front the "gun stack", gun type and manhandling number
+	// take the lower left, lower middle and upper right, so all lines are stacked in the upper left corner instead.
+	//
+	pub fn generate_svg(&self, counter_file: &std::fs::File, front: bool) {
+		if front {
+			let lines: Vec<&TextField> = self.ur_lines.iter().chain(self.ll_lines.iter()).chain(self.um_lines.iter()).chain(self.lm_lines.iter()).collect();
+
+			generate_special_lines(counter_file, &lines, SPECIAL_LEFT_X_POSITION, "start", false);
+		} else {
+			generate_special_lines(counter_file, &self.ll_lines.iter().collect(), SPECIAL_LEFT_X_POSITION, "start", true);
+			generate_special_lines(counter_file, &self.ur_lines.iter().collect(), SPECIAL_RIGHT_X_POSITION, "end", false);
+			generate_special_lines(counter_file, &self.um_lines.iter().collect(), SPECIAL_MIDDLE_X_POSITION, "middle", false);
+			generate_special_lines(counter_file, &self.lm_lines.iter().collect(), SPECIAL_MIDDLE_X_POSITION, "middle", true);
+		}
+	}
+}
+//
+// The generator calls this last, any special entry that wasn't recognized or didn't fit fails the run (after every counter has been
+// written).
+//
+pub fn check_special_errors() -> Result<(), Box<dyn Error>> {
+	let errors = SPECIAL_ERRORS.lock().unwrap();
+
+	if errors.is_empty() {
+		return Ok(());
+	}
+
+	for error in errors.iter() {
+		println!("Special: {error}");
+	}
+
+	return Err(format!("{0} special entries weren't recognized or didn't fit on their counter", errors.len()).into());
+}
+//
+// "NT", "ST" ... with or without a dagger.
+//
+fn is_mount(entry: &String) -> bool {
+	return SPECIAL_MOUNTS.contains(&strip_dagger_and_any_superscript_from_end(entry).trim());
+}
+//
+// Commas inside "[...]" (e.g., "LF [NT, 20, 2 ROF]") don't separate entries, a new line is treated like a comma.
+//
+fn split_special_entries(source: &str) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+	let mut entry: String = Default::default();
+	let mut depth: usize = 0;
+
+	for c in source.chars() {
+		if '[' == c {
+			depth += 1;
+		} else if ']' == c && 0 < depth {
+			depth -= 1;
 		}
-		
-		x_pos = 950;
-		y_pos = 130;
-		
-		for line in &self.ur_lines {
-			write!(counter_file, "\t\t<text x=\"{0}\" y=\"{1}\" text-anchor=\"end\" style=\"font-size:{2}px;font-weight:normal;font-family:{3};fill:{4}\">{5}</text>\n", x_pos, y_pos, line.font_size, FONT_MAIN, line.color, line.text).unwrap();				
-			y_pos += line.font_size;
+
+		if 0 == depth && (SPECIAL_DELIMITER == c || '\n' == c) {
+			result.push(entry.trim().to_string());
+			entry = Default::default();
+		} else {
+			entry.push(c);
 		}
-		
-		x_pos = 500;
-		y_pos = 130;
-		
-		for line in &self.um_lines {
-			write!(counter_file, "\t\t<text x=\"{0}\" y=\"{1}\" text-anchor=\"middle\" style=\"font-size:{2}px;font-weight:normal;font-family:{3};fill:{4}\">{5}</text>\n", x_pos, y_pos, line.font_size, FONT_MAIN, line.color, line.text).unwrap();				
-			y_pos += line.font_size;
-			y_pos += 2 * GUN_COLUMN_Y_GAP; // TODO Superscript handling!?
+	}
+
+	result.push(entry.trim().to_string());
+
+	return result;
+}
+//
+// "5PP", "5PP (3PP dm)", "4PP dm" etc.
+//
+fn is_portage_points(entry: &str) -> bool {
+	let digits: &str = entry.trim_start_matches(|c: char| c.is_ascii_digit());
+
+	return digits.len() < entry.len() && digits.starts_with("PP");
+}
+
+fn new_special_line(text: &String, font_size: f64) -> TextField {
+	let mut result: TextField = Default::default();
+
+	result.fonts.initialize(SPECIAL_FONTS);
+	result.fonts.adjust_size(font_size - SPECIAL_FONTS[FONT_NORMAL][FA_SIZE]);
+	result.fonts.adjust_height(font_size - SPECIAL_FONTS[FONT_NORMAL][FA_HEIGHT]);
+	result.text = text.to_string();
+
+	if result.text.contains("<sup>") {
+		result.text = convert_superscripts(&result.text, result.fonts.sup_size());
+	}
+
+	result.is_set = true;
+
+	return result;
+}
+//
+// Upper lines go down from SPECIAL_TOP_Y_POSITION, lower lines go up from SPECIAL_BOTTOM_Y_POSITION.
+//
+fn generate_special_lines(mut counter_file: &std::fs::File, lines: &Vec<&TextField>, x_position: f64, anchor: &str, lower: bool) {
+	let mut y_position: f64 = if lower { SPECIAL_BOTTOM_Y_POSITION } else { SPECIAL_TOP_Y_POSITION };
+
+	for line in lines {
+		if !lower {
+			y_position += line.fonts.height();
+		}
+
+		write!(counter_file, "\t<text x=\"{0:.2}\" y=\"{1:.2}\" text-anchor=\"{2}\" style=\"font-size:{3:.2}px;{FONT_WEIGHT_NORM};font-family:{4};fill:{5}\">{6}</text>\n", x_position, y_position, anchor, line.fonts.size(), FONT_MAIN, line.color, line.text).unwrap();
+
+		if lower {
+			y_position -= line.fonts.height() + SPECIAL_LINE_GAP;
+		} else {
+			y_position += SPECIAL_LINE_GAP;
 		}
-		
-		y_pos = 950;
-		
-		for line in &self.lm_lines {
-			write!(counter_file, "\t\t<text x=\"{0}\" y=\"{1}\" text-anchor=\"middle\" style=\"font-size:{2}px;font-weight:normal;font-family:{3};fill:{4}\">{5}</text>\n", x_pos, y_pos, line.font_size, FONT_MAIN, line.color, line.text).unwrap();				
-			y_pos -= line.font_size;
-			y_pos -= 2 * GUN_COLUMN_Y_GAP; // TODO Superscript handling!?
-		}		
 	}
 }
-TODO: CREATE_WRECKS/CREATE_MALF_SIDE NOT YET? */
\ No newline at end of file
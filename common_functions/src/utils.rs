@@ -1,6 +1,5 @@
 use std::io::prelude::*;
 use std::{io};
-use std::io::BufReader;
 use std::fs::File;
 use std::path::Path;
 use regex::Regex;
@@ -8,9 +7,13 @@ use regex::Regex;
 // Local files.
 //
 use crate::arguments::*;
+use crate::cached::*;
 use crate::debugging::*;
 use crate::debug_layout;
 use crate::debug_rectangle;
+use crate::defines::*;
+use crate::manifest::*;
+use crate::nationalities::*;
 use crate::overrides::*;
 use crate::text_field::*;
 
@@ -30,6 +33,31 @@ pub fn gun_column_y_gap(mut counter_file: &std::fs::File, x: f64, y: f64, color:
 	return GUN_COLUMN_Y_GAP;
 }
 
+//
+// Smoke values and markings are drawn the same way, starting (or ending, on the right hand side) at the given position.
+//
+pub fn generate_small_text_element(mut counter_file: &std::fs::File, field: &TextField, text: &str, comment: &str, x_position: f64, y_position: f64, anchor: &str) {
+	let x_pos = if "end" == anchor { "100" } else { "0" };
+	let mut postfix_note: String = Default::default();
+
+	if NoteAction::Postfix == field.note.action {
+		postfix_note = field.note.text.to_string();
+	}
+
+	generate_svg_start_element(counter_file, 1, x_position, y_position - field.fonts.height(), 36.0, field.fonts.height(), comment, "white"); // Magic!
+	write!(counter_file, "\t\t<text x=\"{0}%\" y=\"{1}%\" dominant-baseline=\"auto\" text-anchor=\"{2}\"><tspan style=\"font-size:{3:.2}px;{FONT_WEIGHT_BOLD};font-family:{4};fill:{5};fill-opacity:1;stroke-width:0.2\">{6}{7}</tspan></text>\n", x_pos, field.fonts.y_percentage(), anchor, field.fonts.size(), FONT_MAIN, field.color, text, postfix_note).unwrap();
+	write!(counter_file, "\t</svg>\n").unwrap();
+}
+//
+// MOD_LOCATION_MGS moves a smoke value or marking to the right hand side where the MGs would be, like the range and PP # elements,
+// anything else (MOD_LOCATION_ABOVE_MGS, or no "gun stack" to put it in) goes above them.
+//
+pub fn generate_relocated_small_text_element(counter_file: &std::fs::File, field: &TextField, comment: &str) {
+	let y_position: f64 = if MOD_LOCATION_MGS == field.alternate_location { MGS_LINE_Y_POSITION } else { MGS_LINE_2_Y_POSITION };
+
+	generate_small_text_element(counter_file, field, &field.text, comment, MGS_LINE_X_POSITION, y_position, "end");
+}
+
 pub fn strip_all_occurances(original: &str, character: char) -> String {
 	return original.chars().filter(|&x| x != character).collect();
 }
@@ -211,26 +239,15 @@ pub fn convert_text(source: &String, old_text: &str, new_text: &str) -> std::str
 }
 
 pub fn construct_path(nationality: &String, category: &'static str, destination: &String) -> std::string::String {
-	let result: String;
-	
-	if "sh" == nationality {
-		result = format!("{destination}{nationality}/"); // destination includes a trailing '/'	
-	} else {
-		result = format!("{destination}{nationality}/{category}/"); // destination includes a trailing '/'	
-	}
-	
-	return result;
+	return format!("{destination}{0}", nationality_folder(nationality, category)); // destination includes a trailing '/'
 }
 
 pub fn construct_copy_paths(nationality: &String, category: &'static str, name: &String, destination: &String) -> Vec<String> {
 	let mut result: Vec<std::string::String> = Default::default();
-	let cat: String = if !category.is_empty() { format!("{category}/") } else { Default::default() };
+	let (cached, folder) = cached_directories(nationality, category);
 
-	let mut path = format!("./cached/{nationality}/{cat}copy/{name}.svg");
-	result.push(path.clone());	// Source.
-	
-	path = format!("{destination}{nationality}/{cat}{name}.svg"); // destination includes a trailing '/'	
-	result.push(path.clone());	// Destination.	
+	result.push(format!("{cached}{CACHED_COPY_DIRECTORY}/{name}.svg"));	// Source.
+	result.push(format!("{destination}{folder}{name}.svg"));	// Destination, destination includes a trailing '/'.
 	
 	return result;
 }
@@ -243,8 +260,37 @@ pub fn file_exists(path: &String, piece_name: &String) -> bool {
 pub fn open_counter_file(path: &String, piece_name: &String) -> io::Result<File> {
 	File::create(format!("{path}{piece_name}.svg"))
 }
+//
+// A page of the review tools (gallery, counter sheets ...), they stop when it can't be written.
+//
+pub fn write_page(path: &String, page: &String, outputs: &OutputArguments) {
+	if !outputs.quiet {
+		println!("Writing {path}");
+	}
+
+	if let Err(why) = std::fs::write(path, page) {
+		panic!("write_page()@{0}: couldn't write '{1}': {2}", line!(), path, why);
+	}
+}
+//
+// Text content, attribute values also need their quotes escaped.
+//
+pub fn escape_xml(text: &str) -> String {
+	return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+}
 
-pub fn copy_counter(category: &'static str, nationality: &String, piece: &String, note_number: &String, args: &Arguments) -> io::Result<()> {
+pub fn escape_xml_attribute(text: &str) -> String {
+	return escape_xml(text).replace('"', "&quot;");
+}
+
+pub fn unescape_xml(text: &str) -> String {
+	return text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&");
+}
+//
+// A missing cached source is returned as an error naming the piece, the generators stop with that message. "side" is recorded in
+// the manifest (MANIFEST_FRONT or MANIFEST_BACK).
+//
+pub fn copy_counter(category: &'static str, nationality: &String, piece: &String, note_number: &String, side: &str, args: &Arguments) -> io::Result<()> {
 	if !args.quiet {
 		print!("Copying '{0}.svg' ", piece);
 		
@@ -259,21 +305,15 @@ pub fn copy_counter(category: &'static str, nationality: &String, piece: &String
 
 	let paths: Vec<String> = construct_copy_paths(&nationality, &category, &piece, &args.destination);
 
-	let source_file = match File::open(&paths[0]) {
-		Err(why) => panic!("couldn't open file: {0} {1}", &paths[0], why),
-		Ok(counter_file) => counter_file,
-	};
-
-	let mut destination_file = match File::create(&paths[1]) {
-		Err(why) => panic!("couldn't create file: {0} {1}", &paths[1], why),
-		Ok(counter_file) => counter_file,
-	};
+	if let Err(why) = copy_cached_file(&paths[0], &paths[1], false) {
+		if !args.quiet {
+			println!(" failed.");
+		}
 
-	let mut reader = BufReader::new(source_file);
-    let mut buffer = Vec::new();
-    reader.read_to_end(&mut buffer)?;
+		return Err(io::Error::new(why.kind(), format!("couldn't copy '{piece}': {why}")));
+	}
 
-    destination_file.write_all(buffer.as_slice())?;
+	add_manifest_entry(&paths[1].strip_suffix(&format!("{piece}.svg")).unwrap().to_string(), &piece, &nationality, side, MANIFEST_COPIED);
 
 	if !args.quiet {
 		println!(" done.");
@@ -285,8 +325,8 @@ pub fn copy_counter(category: &'static str, nationality: &String, piece: &String
 pub fn get_nationality(nationality_abbreviation: &String) -> std::string::String {
 	let mut nationality = String::from("unknown");
 
-	if "ge" == nationality_abbreviation {
-		nationality = String::from("German");
+	if let Some(found) = find_nationality(nationality_abbreviation) {
+		nationality = found.name.to_string();
 	}
 
 	return nationality;
@@ -0,0 +1,117 @@
+use std::{error::Error, fs, process};
+use std::path::Path;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::cached::*;
+use common_functions::nationalities::*;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct CopyArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long)]
+	check: bool,		// Report the differences instead of copying.
+}
+//
+// Copies the unit depictions (cached/<folder>/svg, the folder being the nationality's output folder, see Nationality::output_folder())
+// into the destination. With "--check" nothing is written, depictions and pre-made counters (cached/<folder>/copy, copied by
+// copy_counter() for rows with the "copy" override) whose destination copy is missing or differs are reported instead. "--nationality"
+// takes groups, codes or aliases (e.g., "german", "sh ML MS").
+//
+fn copy_cached_files(args: &CopyArguments) -> Result<Vec<String>, Box<dyn Error>> {
+	let mut result: Vec<String> = Default::default();
+	let groups: Vec<String> = args.selection.nationality.split_whitespace().map(|group| group.to_string()).collect();
+	let categories: Vec<&str> = if "all" == args.selection.category { CACHED_CATEGORIES.to_vec() } else { vec![args.selection.category.as_str()] };
+
+	for category in &categories {
+		if !CACHED_CATEGORIES.contains(category) {
+			panic!("copy_cached_files()@{0}: unknown category '{1}'", line!(), category);
+		}
+	}
+
+	let mut directories: Vec<(String, String, String, String)> = Default::default();
+
+	for nationality in nationality_codes(&groups) {
+		for category in &categories {
+			let (source, destination) = cached_directories(&nationality, category);
+			//
+			// Nationalities without category folders (e.g., "sh") have the same folders for every category.
+			//
+			if !directories.iter().any(|(_, _, known, _)| *known == source) {
+				directories.push((nationality.to_string(), category.to_string(), source, destination));
+			}
+		}
+	}
+
+	for (nationality, category, source, destination) in &directories {
+		let depictions: String = format!("{CACHED_DEPICTION_DIRECTORY}/");
+
+		if !args.check && Path::new(source).is_dir() {
+			fs::create_dir_all(format!("{0}{destination}", args.outputs.destination))?;	// The generators write their counters there.
+		}
+
+		if Path::new(&format!("{source}{depictions}")).is_dir() && !args.check && !args.outputs.quiet {
+			println!("Copying {nationality} {category} depiction files");
+		}
+
+		for file in list_cached_files(&format!("{source}{depictions}")) {
+			let status: CopyStatus = copy_cached_file(&format!("{source}{depictions}{file}"), &format!("{0}{destination}{depictions}{file}", args.outputs.destination), args.check)?;
+
+			if CopyStatus::Differs == status || CopyStatus::Missing == status {
+				result.push(format!("{status}: {destination}{depictions}{file}"));
+			}
+		}
+
+		if !args.check {
+			continue;
+		}
+		//
+		// Pre-made counters are only copied for some rows, so a missing destination isn't reported.
+		//
+		for file in list_cached_files(&format!("{source}{CACHED_COPY_DIRECTORY}/")) {
+			let status: CopyStatus = copy_cached_file(&format!("{source}{CACHED_COPY_DIRECTORY}/{file}"), &format!("{0}{destination}{file}", args.outputs.destination), args.check)?;
+
+			if CopyStatus::Differs == status {
+				result.push(format!("{status}: {destination}{file}"));
+			}
+		}
+	}
+
+	return Ok(result);
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = CopyArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let differences: Vec<String> = copy_cached_files(&args)?;
+
+	for difference in &differences {
+		println!("{difference}");
+	}
+
+	if !differences.is_empty() {
+		return Err(format!("{0} cached file(s) differ from their destination copy", differences.len()).into());
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
@@ -0,0 +1,185 @@
+use std::{error::Error, fs, process};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use regex::Regex;
+use zip::ZipArchive;
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::build_file::*;
+use common_functions::manifest::*;
+use common_functions::utils::*;
+
+pub const BUILD_FILE_DIRECTORY: &str =	"buildFile/";	// Written to the destination, "<nationality>_<category>.xml" with the PieceSlots.
+
+const BUILD_FILE_ENTRIES: [&str; 2] =	[ "buildFile.xml", "buildFile" ];	// Its name in a module or extension, newer VASSAL versions use the first.
+const IMAGE_EXTENSIONS: [&str; 4] =		[ ".svg", ".gif", ".png", ".jpg" ];
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct ExportArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long, default_value = "")]
+	build_file: String,	// The module's buildFile (or .vmod) to compare the pieces with.
+}
+//
+// What the pieces of an existing buildFile refer to.
+//
+#[derive(Default)]
+struct BuildFile {
+	images: BTreeSet<String>,
+	stems: BTreeMap<String, BTreeSet<String>>,	// "ge/veh/35sf.gif" is found as "35sf".
+	prototypes: BTreeSet<String>,
+}
+
+fn image_stem(image: &str) -> String {
+	return Path::new(image).file_stem().unwrap_or_default().to_string_lossy().to_string();
+}
+//
+// A buildFile on its own or the one in a module (.vmod) or extension (.vmdx, .vmext).
+//
+fn read_build_file(path: &String) -> Result<String, Box<dyn Error>> {
+	let mut result: String = Default::default();
+
+	if !Path::new(path).extension().is_some_and(|extension| [ "vmod", "vmdx", "vmext", "zip" ].contains(&extension.to_string_lossy().to_lowercase().as_str())) {
+		return Ok(fs::read_to_string(path).map_err(|why| format!("couldn't read buildFile '{path}': {why}"))?);
+	}
+
+	let mut archive = ZipArchive::new(File::open(path).map_err(|why| format!("couldn't open '{path}': {why}"))?)?;
+
+	for name in BUILD_FILE_ENTRIES {
+		if let Ok(mut entry) = archive.by_name(name) {
+			entry.read_to_string(&mut result)?;
+			return Ok(result);
+		}
+	}
+
+	return Err(format!("no buildFile in '{path}'").into());
+}
+//
+// The images are found in the PieceSlots' traits (base image, layers ...), with or without extension ("ge/gun/geAT88NTb").
+//
+fn parse_build_file(text: &String) -> BuildFile {
+	let mut result: BuildFile = Default::default();
+	let slot = Regex::new(&format!(r"(?s)<{0}\b[^>]*>(.*?)</{0}>", regex::escape(PIECE_SLOT_ELEMENT))).unwrap();
+	let path = Regex::new(r"^[\w-]+/[\w-]+/[\w.-]+$").unwrap();
+
+	for found in slot.captures_iter(text) {
+		let content: String = unescape_xml(&found[1]).replace('\\', "");
+
+		for token in content.split([ ';', ',', '\t' ]).map(|token| token.trim()) {
+			if IMAGE_EXTENSIONS.iter().any(|extension| token.to_lowercase().ends_with(extension)) || path.is_match(token) {
+				result.images.insert(token.to_string());
+				result.stems.entry(image_stem(token)).or_default().insert(token.to_string());
+			}
+		}
+	}
+
+	for found in Regex::new(&format!(r#"<{0}\b[^>]*\bname="([^"]*)""#, regex::escape(PROTOTYPE_ELEMENT))).unwrap().captures_iter(text) {
+		result.prototypes.insert(unescape_xml(&found[1]));
+	}
+
+	return result;
+}
+//
+// Reports the counters the buildFile doesn't have (by image), and those it has under another image name (e.g., the legacy
+// "ge/veh/35sf.gif" for "ge/veh/35sf.svg").
+//
+fn compare_with_build_file(args: &ExportArguments, counters: &Vec<(&ManifestEntry, Option<&ManifestEntry>)>) -> Result<(), Box<dyn Error>> {
+	let build_file: BuildFile = parse_build_file(&read_build_file(&args.build_file)?);
+	let mut prototypes: BTreeSet<&str> = Default::default();
+	let (mut missing, mut renamed): (usize, usize) = (0, 0);
+
+	for (front, back) in counters {
+		if let Some(prototype) = category_prototype(&front.category) {
+			prototypes.insert(prototype);
+		}
+
+		for entry in [ Some(*front), *back ].into_iter().flatten() {
+			if build_file.images.contains(&entry.output) {
+				continue;
+			}
+
+			if let Some(images) = build_file.stems.get(&image_stem(&entry.output)) {
+				println!("{0} ({1}): renamed, the buildFile has {2}", entry.output, entry.piece, images.iter().cloned().collect::<Vec<String>>().join(", "));
+				renamed += 1;
+			} else {
+				println!("{0} ({1}): missing from the buildFile", entry.output, entry.piece);
+				missing += 1;
+			}
+		}
+	}
+
+	for prototype in prototypes {
+		if !build_file.prototypes.contains(prototype) {
+			println!("Prototype '{prototype}' isn't defined in the buildFile");
+		}
+	}
+
+	println!("{0} counter(s) compared with {1}: {missing} image(s) missing, {renamed} renamed", counters.len(), args.build_file);
+
+	return Ok(());
+}
+//
+// One ListWidget per nationality and category, to paste into the module's buildFile (VASSAL's editor assigns the gpids).
+//
+fn write_fragment(path: &String, title: &String, counters: &Vec<(&ManifestEntry, Option<&ManifestEntry>)>, args: &ExportArguments) -> Result<(), Box<dyn Error>> {
+	let mut result: String = Default::default();
+
+	result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+	result.push_str(&format!("<!-- PieceSlots for the {title} counters, generated from {0} -->\n", escape_xml(&manifest_file(&args.outputs.destination, &args.outputs.manifest))));
+	result.push_str(&list_widget(&args.outputs.destination, title, counters, &mut Default::default(), 0));
+
+	if !args.outputs.quiet {
+		println!("Writing {path} ({0} pieces)", counters.len());
+	}
+
+	fs::write(path, result).map_err(|why| format!("couldn't write '{path}': {why}"))?;
+
+	return Ok(());
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = ExportArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let directory: String = format!("{0}{BUILD_FILE_DIRECTORY}", args.outputs.destination);
+	let groups: BTreeMap<(String, String), Vec<&ManifestEntry>> = group_manifest_entries(&entries, &args.outputs, &args.selection)?;
+	let mut counters: Vec<(&ManifestEntry, Option<&ManifestEntry>)> = Default::default();
+
+	fs::create_dir_all(&directory)?;
+
+	for ((nationality, category), entries) in &groups {
+		let group: Vec<(&ManifestEntry, Option<&ManifestEntry>)> = pair_fronts_and_backs(entries);
+
+		write_fragment(&format!("{directory}{nationality}_{category}.xml"), &format!("{nationality} {category}"), &group, &args)?;
+		counters.extend(group);
+	}
+
+	if !args.build_file.is_empty() {
+		compare_with_build_file(&args, &counters)?;
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
@@ -1,5 +1,4 @@
 use std::io::prelude::*;
-use std::path::Path;
 use std::{error::Error, io, process};
 // This lets us write `#[derive(Deserialize)]`.
 use serde::Deserialize;
@@ -17,8 +16,16 @@ use common_functions::colors::*;
 use common_functions::common_record::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
 use common_functions::machine_guns::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
 use common_functions::text_field::*;
 use common_functions::transport::*;
 use common_functions::utils::*;
@@ -112,6 +119,7 @@ struct Record {
 	mgs: MachineGuns,
 	transport_values: TransportValues,
 	note: String,
+	metadata: CounterMetadata,
 	version: String,
 	piece: String,
 	overrides: Overrides,
@@ -123,20 +131,12 @@ struct Record {
 impl Record {
 	fn generate_aircraft_depiction_svg_elements(&mut self, mut counter_file: &std::fs::File, root_path: &String) {
 		if INCLUDE_IMAGES {
-			let path_prefix = "svg/";
-			let file_type_svg = ".svg";
-			let file_type_png = ".png";
+			let depiction_name: String = self.overrides.depiction(&self.piece);
 
-			let paths: Vec<std::string::String> = [
-				format!("{}{}{}", path_prefix, &self.piece, file_type_svg),
-				format!("{}{}{}", path_prefix, &self.piece, file_type_png)
-			].to_vec();
-
-			for mut path in paths {
-				let mut pathname: String = root_path.to_string();
-				pathname.push_str(&path.to_string());
-
-				if Path::new(&pathname).exists() {
+			match resolve_depiction(root_path, &depiction_name, &self.args) {
+				Some(depiction) => {
+					let href: &String = &depiction.href;
+					let depiction_source: String = depiction.source_attribute();
 					let mut transform: String = "scale(1.00)".to_string();
 
 					if !self.svg_image_transform.is_empty() {
@@ -147,13 +147,11 @@ impl Record {
 						}
 					}
 
-					if path.contains(SPACE) {
-						path = path.replace(SPACE, "%20");
-					}
-
 					write!(counter_file, "\t<!-- Aircraft depiction -->\n").unwrap();
-					write!(counter_file, "\t<image x=\"6\" y=\"6\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{path}\" xlink:href=\"{path}\"/>\n").unwrap();
-					break;	// Our work here is done.
+					write!(counter_file, "\t<image x=\"6\" y=\"6\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{href}\" xlink:href=\"{href}\"{depiction_source}/>\n").unwrap();
+				}
+				None => {
+					missing_depiction(counter_file, root_path, &depiction_name, 12.0, 12.0, 36.0, 36.0, &self.args);
 				}
 			}
 		}
@@ -306,7 +304,7 @@ fn generate_counter_front(counter_file: &std::fs::File, path: &String, record: &
 		
 		generate_aa_svg_elements(&counter_file, &record.aa, &record.colors);
 		
-		generate_rof_svg_elements(&counter_file, &record.rof_aerial, 57.0 - ROF_HEIGHT, 49.0 - ROF_HEIGHT, &"red".to_string(), "Aerial ROF");
+		generate_rof_svg_elements(&counter_file, &record.rof_aerial, 57.0 - ROF_HEIGHT, 49.0 - ROF_HEIGHT, &palette_color(RED), "Aerial ROF");
 		
 		gl_generate_pp_svg_elements(&counter_file, &record.transport_values.pp, GUN_COLUMN_X_POSITION, GUN_COLUMN_Y_POSITION, &"start".to_string());
 	}
@@ -423,7 +421,9 @@ fn generate_counter(record: &mut Record, note_number: &String) {
 		Ok(counter_file) => counter_file,
 	};
 
-	generate_counter_header_svg_elements("vasl_aircraft_counters", &counter_file, 60, &record.name, &note_number, &record.comments, &record.version);
+	add_manifest_entry(&path, &record.piece, &record.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
+
+	generate_counter_header_svg_elements("vasl_aircraft_counters", &counter_file, 60, &record.name, &note_number, &record.comments, &record.version, &record.metadata.for_piece(&record.piece));
 	generate_counter_front(&counter_file, &path, record);
 	generate_footer_svg(&counter_file);
 
@@ -523,6 +523,10 @@ impl SpreadsheetRecord {
 		
 		result.date.text = self.date.to_string();
 		
+		result.metadata = CounterMetadata::new("aircraft", &result.nationality);
+		result.metadata.notes = strip_html_tags(&self.notes);
+		result.metadata.dates = strip_html_tags(&self.date);
+		
 		if !self.aa.is_empty() {
 			result.aa.text = self.aa.to_string();
 			result.aa.sanitize(&self.aa.to_string(), &"".to_string(), AA_FONTS, &result.colors);
@@ -593,19 +597,37 @@ fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
-	args.destination.push_str("sh/");
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
+	//
+	// The counters go to the shared folder, the manifest (and the shared defs) stay in the destination.
+	//
+	let mut shared: Arguments = args.clone();
+
+	shared.destination = construct_path(&SHARED_NATIONALITY.to_string(), "veh", &args.destination);
 	
+	let manifest: ManifestWriter = ManifestWriter::new("veh", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
+
+		set_manifest_source_row(&row);
 
 		if !spreadsheet_record.overrides.contains(NOVR_IGNORE) {
-			let mut record: Record = spreadsheet_record.sanitize(&args);
+			let mut record: Record = spreadsheet_record.sanitize(&shared);
 			
 			generate_counters(&mut record);
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
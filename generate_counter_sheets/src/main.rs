@@ -0,0 +1,326 @@
+use std::{error::Error, fs, process};
+use std::collections::BTreeMap;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref};
+use regex::Regex;
+use svg2pdf::usvg;
+use svg2pdf::usvg::{TreeParsing, TreePostProc};
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::build_file::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::utils::*;
+
+pub const SHEETS_DIRECTORY: &str =	"sheets/";	// Written to the destination, one SVG per page and one PDF per sheet.
+
+const PIXELS_PER_INCH: f64 =		96.0;		// The counters' pixels, 60 is a 5/8" vehicle or gun, 48 a 1/2" infantry or SW counter.
+const POINTS_PER_PIXEL: f64 =		0.75;		// PDF points are 1/72".
+const SHEET_MARGIN: f64 =			48.0;		// At least 1/2" all around, most printers can't print closer to the edge.
+const BLEED: f64 =					6.0;		// 1/16" of the counter's color around it so that a cut a little off doesn't show white.
+const CUT_MARK_GAP: f64 =			3.0;
+const CUT_MARK_LENGTH: f64 =		15.0;
+const LABEL_FONT_SIZE: f64 =		8.0;
+//
+// Name, width and height (in pixels, at PIXELS_PER_INCH) of the paper sizes known to "--paper".
+//
+const PAPER_SIZES: [(&str, f64, f64); 2] = [
+	( "a4",		793.70,	1122.52 ),	// 210 x 297 mm
+	( "letter",	816.00,	1056.00 ),	// 8.5 x 11"
+];
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct SheetsArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long, default_value = "a4")]
+	paper: String,		// "a4" or "letter".
+}
+//
+// A counter and its back (piece "<piece>b" in the same folder, e.g., broken MMC, malfunctioned guns and SW), if it has one.
+//
+struct Counter {
+	front: String,
+	back: Option<String>,
+}
+//
+// Counters of a nationality, category and size, printed together.
+//
+struct Sheet {
+	nationality: String,
+	category: String,
+	size: u32,
+	counters: Vec<Counter>,
+}
+
+struct Layout {
+	width: f64,
+	height: f64,
+	columns: usize,
+	rows: usize,
+	left: f64,
+	top: f64,
+	pitch: f64,
+}
+
+impl Layout {
+	fn new(paper: &String, size: u32) -> Layout {
+		let Some((_, width, height)) = PAPER_SIZES.iter().find(|(name, _, _)| paper == name) else {
+			panic!("Layout::new()@{0}: unknown paper '{1}' (use {2})", line!(), paper, PAPER_SIZES.map(|(name, _, _)| format!("'{name}'")).join(" or "));
+		};
+		let pitch: f64 = size as f64 + 2.0 * BLEED;
+		let columns: usize = ((width - 2.0 * SHEET_MARGIN) / pitch).floor() as usize;
+		let rows: usize = ((height - 2.0 * SHEET_MARGIN) / pitch).floor() as usize;
+
+		if 0 == columns || 0 == rows {
+			panic!("Layout::new()@{0}: {1}px counters don't fit on '{2}'", line!(), size, paper);
+		}
+
+		return Layout {
+			width: *width,
+			height: *height,
+			columns,
+			rows,
+			left: (width - columns as f64 * pitch) / 2.0,
+			top: (height - rows as f64 * pitch) / 2.0,
+			pitch,
+		};
+	}
+
+	fn capacity(&self) -> usize {
+		return self.columns * self.rows;
+	}
+}
+
+fn read_counter(path: &String) -> String {
+	return match fs::read_to_string(path) {
+		Err(why) => panic!("read_counter()@{0}: couldn't read '{1}': {2}", line!(), path, why),
+		Ok(text) => text,
+	};
+}
+//
+// The fill of the counter's background (<rect id="color" ...>) is also the bleed's.
+//
+fn background_color(text: &String) -> String {
+	return match Regex::new(r#"<rect id="color"[^>]*?fill:([^;"]+)"#).unwrap().captures(text) {
+		Some(found) => found[1].trim().to_string(),
+		None => "white".to_string(),
+	};
+}
+//
+// The manifest's counters of the selected nationalities and category, backs aren't sheet positions of their own.
+//
+fn collect_sheets(args: &SheetsArguments, groups: &BTreeMap<(String, String), Vec<&ManifestEntry>>) -> Vec<Sheet> {
+	let mut sheets: BTreeMap<(String, String, u32), Vec<Counter>> = Default::default();
+
+	for (front, back) in groups.values().flat_map(pair_fronts_and_backs) {
+		let size: u32 = counter_size(&format!("{0}{1}", args.outputs.destination, front.output));
+
+		sheets.entry((front.nationality.to_string(), front.category.to_string(), size)).or_default().push(Counter {
+			front: front.output.to_string(),
+			back: back.map(|back| back.output.to_string()),
+		});
+	}
+
+	return sheets.into_iter().map(|((nationality, category, size), counters)| Sheet {
+		nationality,
+		category,
+		size,
+		counters,
+	}).collect();
+}
+//
+// The counter (images inlined, ids prefixed so that counters can't clash) with its bleed, at the position's top left corner.
+//
+fn placed_counter(args: &SheetsArguments, output: &String, x: f64, y: f64, size: u32, number: usize) -> String {
+	let path: String = format!("{0}{1}", args.outputs.destination, output);
+	let (text, _) = inline_images(&path, &read_counter(&path));
+	let viewport: Vec<(String, String)> = vec![
+		("x".to_string(), format!("{x:.2}")),
+		("y".to_string(), format!("{y:.2}")),
+		("width".to_string(), size.to_string()),
+		("height".to_string(), size.to_string()),
+	];
+	let bleed: String = format!("\t<rect x=\"{0:.2}\" y=\"{1:.2}\" width=\"{2:.2}\" height=\"{2:.2}\" style=\"fill:{3};stroke:none\"/>\n", x - BLEED, y - BLEED, size as f64 + 2.0 * BLEED, background_color(&text));
+
+	return format!("\t<!-- {output} -->\n{bleed}\t{0}\n", embed_svg(&text, &path, &viewport, &format!("c{number}_")));
+}
+//
+// Short lines in the margins, in line with every cut (the counters' edges).
+//
+fn cut_marks(layout: &Layout) -> String {
+	let mut result: String = "\t<g style=\"stroke:#000000;stroke-width:0.5;fill:none\">\n".to_string();
+	let right: f64 = layout.left + layout.columns as f64 * layout.pitch;
+	let bottom: f64 = layout.top + layout.rows as f64 * layout.pitch;
+
+	for column in 0..layout.columns {
+		let x: f64 = layout.left + column as f64 * layout.pitch + BLEED;
+
+		for x in [x, x + layout.pitch - 2.0 * BLEED] {
+			result.push_str(&format!("\t\t<line x1=\"{x:.2}\" y1=\"{0:.2}\" x2=\"{x:.2}\" y2=\"{1:.2}\"/>\n", layout.top - CUT_MARK_GAP - CUT_MARK_LENGTH, layout.top - CUT_MARK_GAP));
+			result.push_str(&format!("\t\t<line x1=\"{x:.2}\" y1=\"{0:.2}\" x2=\"{x:.2}\" y2=\"{1:.2}\"/>\n", bottom + CUT_MARK_GAP, bottom + CUT_MARK_GAP + CUT_MARK_LENGTH));
+		}
+	}
+
+	for row in 0..layout.rows {
+		let y: f64 = layout.top + row as f64 * layout.pitch + BLEED;
+
+		for y in [y, y + layout.pitch - 2.0 * BLEED] {
+			result.push_str(&format!("\t\t<line x1=\"{0:.2}\" y1=\"{y:.2}\" x2=\"{1:.2}\" y2=\"{y:.2}\"/>\n", layout.left - CUT_MARK_GAP - CUT_MARK_LENGTH, layout.left - CUT_MARK_GAP));
+			result.push_str(&format!("\t\t<line x1=\"{0:.2}\" y1=\"{y:.2}\" x2=\"{1:.2}\" y2=\"{y:.2}\"/>\n", right + CUT_MARK_GAP, right + CUT_MARK_GAP + CUT_MARK_LENGTH));
+		}
+	}
+
+	result.push_str("\t</g>\n");
+
+	return result;
+}
+//
+// One page of fronts or backs. Backs are mirrored left to right so that they line up with their fronts when the sheet is printed
+// two-sided (flipped on the long edge) or the pages are glued back to back.
+//
+fn sheet_page(args: &SheetsArguments, sheet: &Sheet, layout: &Layout, counters: &[Counter], backs: bool, label: &String) -> String {
+	let size: u32 = sheet.size;
+	let mut result: String = Default::default();
+
+	result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+	result.push_str(&format!("<svg width=\"{0:.2}\" height=\"{1:.2}\" viewBox=\"0 0 {0:.2} {1:.2}\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\" xmlns:svg=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">\n", layout.width, layout.height));
+	result.push_str(&format!("\t<title>{label}</title>\n"));
+
+	for (index, counter) in counters.iter().enumerate() {
+		let row: usize = index / layout.columns;
+		let column: usize = if backs { layout.columns - 1 - index % layout.columns } else { index % layout.columns };
+		let x: f64 = layout.left + column as f64 * layout.pitch + BLEED;
+		let y: f64 = layout.top + row as f64 * layout.pitch + BLEED;
+		let output: Option<&String> = if backs { counter.back.as_ref() } else { Some(&counter.front) };
+
+		if let Some(output) = output {
+			result.push_str(&placed_counter(args, output, x, y, size, index));
+		}
+	}
+
+	result.push_str(&cut_marks(layout));
+	result.push_str(&format!("\t<text x=\"{0:.2}\" y=\"{1:.2}\" style=\"font-family:sans-serif;font-size:{LABEL_FONT_SIZE}px;fill:#808080\">{label}</text>\n", layout.left, layout.height - SHEET_MARGIN / 2.0));
+	result.push_str("</svg>\n");
+
+	return result;
+}
+//
+// The pages one after the other (fronts and backs alternating), each page's SVG drawn by svg2pdf with text converted to paths.
+//
+fn write_pdf(path: &String, pages: &Vec<String>, layout: &Layout, fonts: &usvg::fontdb::Database) -> Result<(), Box<dyn Error>> {
+	let mut pdf: Pdf = Pdf::new();
+	let catalog: Ref = Ref::new(1);
+	let page_tree: Ref = Ref::new(2);
+	let mut next: Ref = Ref::new(3);
+	let mut page_ids: Vec<Ref> = Default::default();
+	let name: Name = Name(b"S1");
+	let options = svg2pdf::Options {
+		dpi: PIXELS_PER_INCH as f32,
+		..Default::default()
+	};
+
+	for page in pages {
+		let page_id: Ref = next;
+		let content_id: Ref = Ref::new(page_id.get() + 1);
+		let svg_id: Ref = Ref::new(page_id.get() + 2);
+		let mut tree: usvg::Tree = usvg::Tree::from_str(page, &usvg::Options::default())?;
+
+		tree.postprocess(Default::default(), fonts);
+		next = svg2pdf::convert_tree_into(&tree, options, &mut pdf, svg_id);
+
+		let (width, height): (f32, f32) = ((layout.width * POINTS_PER_PIXEL) as f32, (layout.height * POINTS_PER_PIXEL) as f32);
+		let mut content: Content = Content::new();
+
+		content.transform([width, 0.0, 0.0, height, 0.0, 0.0]);
+		content.x_object(name);
+		pdf.stream(content_id, &content.finish());
+
+		let mut pdf_page = pdf.page(page_id);
+
+		pdf_page.media_box(Rect::new(0.0, 0.0, width, height));
+		pdf_page.parent(page_tree);
+		pdf_page.contents(content_id);
+		pdf_page.resources().x_objects().pair(name, svg_id);
+		pdf_page.finish();
+
+		page_ids.push(page_id);
+	}
+
+	pdf.catalog(catalog).pages(page_tree);
+	pdf.pages(page_tree).count(page_ids.len() as i32).kids(page_ids);
+
+	fs::write(path, pdf.finish())?;
+
+	return Ok(());
+}
+
+//
+// Writes "<nationality>_<category>_<size>_front_<n>.svg" (and "..._back_<n>.svg" when any of the sheet's counters has a back) and
+// "<nationality>_<category>_<size>.pdf" with every page, fronts followed by their backs.
+//
+fn write_sheet(args: &SheetsArguments, sheet: &Sheet, fonts: &usvg::fontdb::Database) -> Result<usize, Box<dyn Error>> {
+	let layout: Layout = Layout::new(&args.paper, sheet.size);
+	let name: String = format!("{0}_{1}_{2}", sheet.nationality, sheet.category, sheet.size);
+	let has_backs: bool = sheet.counters.iter().any(|counter| counter.back.is_some());
+	let pages: usize = sheet.counters.len().div_ceil(layout.capacity());
+	let mut result: Vec<String> = Default::default();
+
+	for (index, counters) in sheet.counters.chunks(layout.capacity()).enumerate() {
+		let sides: Vec<&str> = if has_backs { vec![ "front", "back" ] } else { vec![ "front" ] };
+
+		for side in sides {
+			let label: String = format!("{0} {1} {2} {3}/{pages} - print at actual size (100%) on {4}, cut on the marks", sheet.nationality, sheet.category, side, index + 1, args.paper);
+			let page: String = sheet_page(args, sheet, &layout, counters, "back" == side, &label);
+			let path: String = format!("{0}{SHEETS_DIRECTORY}{name}_{side}_{1}.svg", args.outputs.destination, index + 1);
+
+			write_page(&path, &page, &args.outputs);
+			result.push(page);
+		}
+	}
+
+	write_pdf(&format!("{0}{SHEETS_DIRECTORY}{name}.pdf", args.outputs.destination), &result, &layout, fonts)?;
+
+	return Ok(result.len());
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = SheetsArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let sheets: Vec<Sheet> = collect_sheets(&args, &group_manifest_entries(&entries, &args.outputs, &args.selection)?);
+	let mut fonts = usvg::fontdb::Database::new();
+	let mut pages: usize = 0;
+
+	fs::create_dir_all(format!("{0}{SHEETS_DIRECTORY}", args.outputs.destination))?;
+	fonts.load_system_fonts();
+
+	for sheet in &sheets {
+		pages += write_sheet(&args, sheet, &fonts)?;
+	}
+
+	if !args.outputs.quiet {
+		println!("{0} sheet(s), {pages} page(s) written to {1}{SHEETS_DIRECTORY}", sheets.len(), args.outputs.destination);
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
@@ -17,10 +17,18 @@ use common_functions::colors::*;
 use common_functions::common_record::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
 use common_functions::machine_guns::*;
 use common_functions::malfunction::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
 use common_functions::movement::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
 use common_functions::text_field::*;
 use common_functions::transport::*;
 use common_functions::utils::*;
@@ -74,7 +82,7 @@ fn generate_armament_elements(counter_file: &std::fs::File, record: &Record) {
 		y_position -= generate_malfunction_elements(&counter_file, &record.sa_malfunction, y_position);
 
 		if !record.common.ma.rof.text.is_empty() {
-			y_position -= generate_rof_element(&counter_file, &record.common.ma.rof, y_position, &record.common.ma.color, record.common.overrides.ma.multiple_hits);
+			y_position -= generate_rof_element(&counter_file, &record.common.ma.rof, y_position, &record.common.ma.color, record.common.overrides.ma.multiple_hits, false);
 		}	
 
 		if !record.common.ma.ife.text.is_empty() && !record.common.ma.ife.alternate_location.is_empty() {
@@ -97,7 +105,7 @@ fn generate_counter_front(mut counter_file: &std::fs::File, path: &String, recor
 	generate_counter_background_svg(counter_file, 60, &record.common.colors, &record.common.overrides);
 	generate_debug_working_area_svg(&counter_file);
 	record.common.turret.generate_svg_elements(&counter_file);
-	generate_unit_depiction_svg(counter_file, &path, &record.common.piece_front, &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
+	generate_unit_depiction_svg(counter_file, &path, &record.common.overrides.depiction(&record.common.piece_front), &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
 	generate_armament_elements(&counter_file, &record); // Construct the whole "gun stack" of information, containing (potentially) the gun caliber, ROF, breakdown number, IFE, PP #, etc.).
 	record.armor.generate_svg_elements(&counter_file);
 	record.mgs.generate_svg_elements(&counter_file);
@@ -222,8 +230,10 @@ fn generate_counter(record: &mut Record, note_number: &String) {
 		Err(why) => panic!("couldn't create file: {0} {1}", record.common.piece_front, why),
 		Ok(counter_file) => counter_file,
 	};
+
+	add_manifest_entry(&path, &record.common.piece_front, &record.common.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
 	
-	generate_counter_header_svg_elements("vasl_landing_craft_and_boats_counters", &counter_file, 60, &record.common.name, &note_number, &record.common.comments, &record.common.version);
+	generate_counter_header_svg_elements("vasl_landing_craft_and_boats_counters", &counter_file, 60, &record.common.name, &note_number, &record.common.comments, &record.common.version, &record.common.metadata.for_piece(&record.common.piece_front));
 	generate_counter_front(&counter_file, &path, record);
 	generate_footer_svg(&counter_file);
 	
@@ -313,6 +323,10 @@ impl SpreadsheetRecord {
 		
 		result.common.initialize(&nationality, &self.notes, &self.name, &self.ma, &"".to_string(), &self.rof_ife, &self.breakdown, &self.version, &self.piece, &self.svg_image_transform, &self.comments);
 		
+		result.common.metadata = CounterMetadata::new("landing_craft", &nationality);
+		result.common.metadata.set_chapter_h_values(&self.notes, &self.bpv, &self.rf, &self.dates);
+		result.common.metadata.count = strip_html_tags(&self.count);
+		
 		result.common.turret = sanitize_mount(&self.gt, &result.common.overrides, &result.common.colors);
 		
 		result.armor.initialize(&self.af, &self.ta, &self.size, &result.common.overrides, &result.common.colors);
@@ -347,28 +361,40 @@ fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
-	args.destination.push_str("sh/");
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
+	//
+	// The counters go to the shared folder, the manifest (and the shared defs) stay in the destination.
+	//
+	let mut shared: Arguments = args.clone();
+
+	shared.destination = construct_path(&SHARED_NATIONALITY.to_string(), "veh", &args.destination);
 	
+	let manifest: ManifestWriter = ManifestWriter::new("veh", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
+
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+		set_manifest_source_row(&row);
 
 		if NOVR_ANNOUNCE == spreadsheet_record.overrides {
 			if !args.quiet {
 				println!("{}", strip_html_bold(&spreadsheet_record.count));
 			}
 		} else if !spreadsheet_record.overrides.contains(NOVR_IGNORE) {
-			let mut record: Record = spreadsheet_record.sanitize(&"".to_string(), &args);
+			let mut record: Record = spreadsheet_record.sanitize(&"".to_string(), &shared);
 			let pieces = record.common.pieces.clone();
 			
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.common.piece_front = piece.to_string();
 					generate_counters(&mut record);
 				} else {
-					let (piece_name, nationality) = piece.split_once("@").unwrap();
-					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
+					let (piece_name, nationality) = split_alternate_nationality(&piece);
+					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &shared);
 					
 					alt_record.common.colors = nationality_to_colors(&nationality.to_string());
 					alt_record.common.piece_front = piece_name.to_string();
@@ -378,6 +404,12 @@ fn run() -> Result<(), Box<dyn Error>> {
 			}
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
@@ -9,6 +9,7 @@ use clap::Parser;
 // Local files.
 //
 use common_functions::arguments::*;
+use common_functions::manifest::*;
 use common_functions::overrides::*;
 use common_functions::utils::*;
 //
@@ -25,10 +26,12 @@ struct Record {
 	comments: String,
 }
 
-fn process_counter(record: &mut Record) {
+fn process_counter(record: &mut Record) -> io::Result<()> {
 	if record.overrides.copy {
-		let _ = copy_counter("", &record.folder, &record.piece, &"".to_string(), &record.args);
+		copy_counter("", &record.folder, &record.piece, &"".to_string(), MANIFEST_FRONT, &record.args)?;
 	}
+
+	Ok(())
 }
 //
 // We don't need to derive `Debug` (which doesn't require Serde), but it's a
@@ -76,17 +79,25 @@ fn run() -> Result<(), Box<dyn Error>> {
 	
 	args.sanitize_destination();
 	
+	let manifest: ManifestWriter = ManifestWriter::new("misc", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
+
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+		set_manifest_source_row(&row);
 
 		if !spreadsheet_record.folder.is_empty() {
 			let mut record: Record = spreadsheet_record.sanitize(&args);
 			
-			process_counter(&mut record);
+			process_counter(&mut record)?;
 		}
 	}
+
+	manifest.write();
+
 	Ok(())
 }
 
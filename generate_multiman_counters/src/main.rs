@@ -1,5 +1,5 @@
 use std::io::prelude::*;
-use std::path::Path;
+use std::fs::File;
 use std::{error::Error, io, process};
 use std::fmt;
 // This lets us write `#[derive(Deserialize)]`.
@@ -16,7 +16,15 @@ use common_functions::arguments::*;
 use common_functions::colors::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
 use common_functions::text_field::*;
 use common_functions::utils::*;
 
@@ -50,6 +58,9 @@ pub const BROKEN_MORALE_SIZE: f64 =				16.0;
 pub const BROKEN_MORALE_FONT_SIZE: f64 =		14.0;
 pub const BROKEN_MORALE_STROKE_WIDTH: f64 =		 0.75;
 
+pub const INFORMATIONAL_CLASS_PREFIX: char =	'?';	// Concealment, cloaking, ?/ROI and any other marker described in INFORMATIONAL_TEMPLATES_FILE.
+pub const INFORMATIONAL_TEMPLATES_FILE: &str =	"./data/mmc_markers.csv";
+
 #[derive(PartialEq)]
 #[derive(Default)]
 #[derive(Clone)]
@@ -87,11 +98,7 @@ pub enum ClassIdentifier {
 	KoreanMarines,
 	Fn16,
 	Broken,
-	Cloaking,
-	Concealment,
-	LargeConcealment,
-	MiniConcealment,
-	Roi,
+	Informational,
 }
 
 impl fmt::Display for ClassIdentifier {
@@ -129,11 +136,7 @@ impl fmt::Display for ClassIdentifier {
 			ClassIdentifier::KoreanMarines => write!(f, "KoreanMarines"),
 			ClassIdentifier::Fn16 => write!(f, "Fn16"),
 			ClassIdentifier::Broken => write!(f, "Broken"),
-			ClassIdentifier::Cloaking => write!(f, "Cloaking"),
-			ClassIdentifier::Concealment => write!(f, "Concealment"),
-			ClassIdentifier::LargeConcealment => write!(f, "LargeConcealment"),
-			ClassIdentifier::MiniConcealment => write!(f, "MiniConcealment"),
-			ClassIdentifier::Roi => write!(f, "Roi"),
+			ClassIdentifier::Informational => write!(f, "Informational"),
         }
     }
 }
@@ -145,6 +148,7 @@ struct Record {
 	args: Arguments,
 	nationality: String,
 	class: ClassIdentifier,
+	informational: String,	// Template key (the "class" column) for informational counters.
 	firepower: usize,
 	range: String, // Thanks Communist Chinese Grenadier squads ...
 	morale: usize,
@@ -166,6 +170,38 @@ struct Record {
 }
 
 impl Record {
+	//
+	// Every piece of an entry shares the same values, only the piece name differs.
+	//
+	fn metadata(&self) -> CounterMetadata {
+		let mut result: CounterMetadata = CounterMetadata::new("multiman", &self.nationality).for_piece(&self.piece);
+
+		if 0 != self.bpv {
+			result.bpv = self.bpv.to_string();
+		}
+
+		return result;
+	}
+
+	//
+	// The broken sides a squad, half squad or crew can have besides its own "<piece>b": the KFW ones ("<piece>-bkn") and the one
+	// its nationality shares by size (the capital after the values, "ge838S" and "am558pS" are squads) and broken morale, "ges9b"
+	// ("nkS9b").
+	//
+	fn broken_side_pieces(&self) -> Vec<String> {
+		let mut result: Vec<String> = vec![ format!("{0}-bkn", self.piece) ];
+		let rest: &str = self.piece.strip_prefix(self.nationality.as_str()).unwrap_or_default();
+
+		if rest.chars().take(3).filter(|c| c.is_ascii_digit()).count() == 3 {
+			if let Some(size) = rest.chars().skip(3).find(|c| !c.is_ascii_lowercase()).filter(|size| [ 'S', 'H', 'C' ].contains(size)) {
+				result.push(format!("{0}{1}{2}b", self.nationality, size.to_ascii_lowercase(), self.broken_morale));
+				result.push(format!("{0}{size}{1}b", self.nationality, self.broken_morale));
+			}
+		}
+
+		return result;
+	}
+
 	fn sanitize_class_identifier(&mut self, class: &str) {
 		match class {
 			"ss" => {
@@ -261,32 +297,17 @@ impl Record {
 			"broken" => {
 				self.class = ClassIdentifier::Broken;
 				
-			}
-			"?C" => {
-				self.class = ClassIdentifier::Cloaking;
-				
-			}
-			"?" => {
-				self.class = ClassIdentifier::Concealment;
-				
-			}
-			"?58" => {
-				self.class = ClassIdentifier::LargeConcealment;
-				
-			}
-			"?me" => {
-				self.class = ClassIdentifier::MiniConcealment;
-				
-			}
-			"?roi" => {
-				self.class = ClassIdentifier::Roi;
-				
 			}
 			"none" => {
 				self.class = ClassIdentifier::None;
 			}
 			&_ => {
-				panic!("sanitize_class_identifier() - '{class}' unrecognized value!");
+				if class.starts_with(INFORMATIONAL_CLASS_PREFIX) {
+					self.class = ClassIdentifier::Informational;
+					self.informational = class.to_string();
+				} else {
+					panic!("sanitize_class_identifier() - '{class}' unrecognized value!");
+				}
 			}
 		}
 	}	
@@ -305,22 +326,14 @@ impl Record {
 
 	fn generate_unit_depiction_svg_elements(&mut self, mut counter_file: &std::fs::File, root_path: &String) {
 		if INCLUDE_IMAGES {
-			let path_prefix = "svg/";
-			let file_type_svg = ".svg";
-			let file_type_png = ".png";
-	
-			let paths: Vec<std::string::String> = [
-				format!("{}{}{}", path_prefix, &self.piece, file_type_svg),
-				format!("{}{}{}", path_prefix, &self.piece, file_type_png)
-			].to_vec();
-	
-			for mut path in paths {
-				let mut pathname: String = root_path.to_string();
-				pathname.push_str(&path.to_string());
-	
-				if Path::new(&pathname).exists() {
+			let depiction_name: String = self.overrides.depiction(&self.piece);
+
+			match resolve_depiction(root_path, &depiction_name, &self.args) {
+				Some(depiction) => {
+					let href: &String = &depiction.href;
+					let depiction_source: String = depiction.source_attribute();
 					let mut transform: String = "scale(1.00)".to_string();
-	
+
 					if !self.svg_image_transform.is_empty() {
 						if self.svg_image_transform.contains("scale") {
 							transform = self.svg_image_transform.to_string();
@@ -328,14 +341,14 @@ impl Record {
 							transform = format!("{transform} {0}", self.svg_image_transform);
 						}
 					}
-	
-					if path.contains(SPACE) {
-						path = path.replace(SPACE, "%20");
-					}
-	
+
 					write!(counter_file, "\t<!-- Silhouettes -->\n").unwrap();
-					write!(counter_file, "\t<image x=\"0\" y=\"0\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{path}\" xlink:href=\"{path}\"/>\n").unwrap();
-					break;	// Our work here is done.
+					write!(counter_file, "\t<image x=\"0\" y=\"0\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{href}\" xlink:href=\"{href}\"{depiction_source}/>\n").unwrap();
+				}
+				None => {
+					if ClassIdentifier::None != self.class { // Blank counters have no depiction.
+						missing_depiction(counter_file, root_path, &depiction_name, 9.0, 9.0, 30.0, 30.0, &self.args);
+					}
 				}
 			}
 		}
@@ -343,23 +356,14 @@ impl Record {
 
 	fn generate_broken_unit_depiction_svg_elements(&mut self, mut counter_file: &std::fs::File, root_path: &String) {
 		if INCLUDE_IMAGES {
-			let path_prefix = "svg/";
-			let file_type_svg = ".svg";
-			let file_type_png = ".png";
-			let filename: String = self.piece.to_string();
-			let paths: Vec<std::string::String> = [
-				format!("{}{}{}", path_prefix, &filename, file_type_svg),
-				format!("{}{}{}", path_prefix, &filename, file_type_png)
-			].to_vec();
-	
-	
-			for mut path in paths {
-				let mut pathname: String = root_path.to_string();
-				pathname.push_str(&path.to_string());
-	
-				if Path::new(&pathname).exists() {
+			let depiction_name: String = self.overrides.depiction(&self.piece);
+
+			match resolve_depiction(root_path, &depiction_name, &self.args) {
+				Some(depiction) => {
+					let href: &String = &depiction.href;
+					let depiction_source: String = depiction.source_attribute();
 					let mut transform: String = "scale(1.00)".to_string();
-	
+
 					if !self.svg_image_transform.is_empty() {
 						if self.svg_image_transform.contains("scale") {
 							transform = self.svg_image_transform.to_string();
@@ -367,13 +371,11 @@ impl Record {
 							transform = format!("{transform} {0}", self.svg_image_transform);
 						}
 					}
-	
-					if path.contains(SPACE) {
-						path = path.replace(SPACE, "%20");
-					}
-	
-					write!(counter_file, "\t<image id=\"Silhouettes\" x=\"0\" y=\"0\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{path}\" xlink:href=\"{path}\"/>\n").unwrap();
-					break;	// Our work here is done.
+
+					write!(counter_file, "\t<image id=\"Silhouettes\" x=\"0\" y=\"0\" width=\"48\" height=\"48\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{href}\" xlink:href=\"{href}\"{depiction_source}/>\n").unwrap();
+				}
+				None => {
+					missing_depiction(counter_file, root_path, &depiction_name, 9.0, 9.0, 30.0, 30.0, &self.args);
 				}
 			}
 		}
@@ -609,8 +611,6 @@ fn generate_svg_counter(record: &mut Record) {
 	
 	if 0 != record.overrides.counter_size {
 		size = record.overrides.counter_size;
-	} else if ClassIdentifier::LargeConcealment == record.class {
-		size = 60;
 	}
 	//
 	// Create the counter file.
@@ -620,7 +620,14 @@ fn generate_svg_counter(record: &mut Record) {
 		Ok(counter_file) => counter_file,
 	};
 
-	generate_counter_header_svg_elements("vasl_multiman_counters", &counter_file, size, &record.piece, &"".to_string(), &record.comments, &record.version);
+	if ClassIdentifier::Broken == record.class {
+		add_manifest_entry(&path, &record.piece, &record.nationality, MANIFEST_BACK, MANIFEST_GENERATED);
+	} else {
+		add_manifest_entry(&path, &record.piece, &record.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
+		add_manifest_backs(&path, &record.piece, &record.broken_side_pieces());
+	}
+
+	generate_counter_header_svg_elements("vasl_multiman_counters", &counter_file, size, &record.piece, &"".to_string(), &record.comments, &record.version, &record.metadata());
 	generate_counter_background_svg(&counter_file, size, &record.colors, &record.overrides);
 	generate_debug_working_area_svg(&counter_file);
 	
@@ -628,21 +635,6 @@ fn generate_svg_counter(record: &mut Record) {
 		ClassIdentifier::Broken => {
 			generate_broken_counter_svg_elements(&counter_file, record, &path);
 		}
-		ClassIdentifier::Cloaking => {
-			generate_cloaking_counter_svg_elements(&counter_file, record);
-		}
-		ClassIdentifier::Concealment => {
-			generate_concealment_counter_svg_elements(&counter_file, record);
-		}
-		ClassIdentifier::LargeConcealment => {
-			generate_large_concealment_counter_svg_elements(&counter_file, record);
-		}
-		ClassIdentifier::MiniConcealment => {
-			generate_mini_concealment_counter_svg_elements(&counter_file, record);
-		}
-		ClassIdentifier::Roi => {
-			generate_roi_counter_svg_elements(&counter_file, record);
-		}
 		_ => {
 			generate_multiman_counter_svg_elements(&counter_file, record, &path);
 		}
@@ -653,10 +645,17 @@ fn generate_svg_counter(record: &mut Record) {
 	drop(counter_file);
 }
 
-fn generate_mini_concealment_svg_counter(record: &mut Record) {
+//
+// Informational counters (concealment, cloaking, ?/ROI ...) are laid out entirely by their INFORMATIONAL_TEMPLATES_FILE row.
+//
+fn generate_informational_svg_counter(record: &mut Record, template: &InformationalTemplateRecord) {
 	let path = &record.args.destination.to_string();
-	let size = 48;
-	let mini_size = 24;
+	let mut size: u32 = template.counter_size.parse::<u32>().unwrap_or(48);
+	let background_size: u32 = template.background_size.parse::<u32>().unwrap_or(size);
+	
+	if 0 != record.overrides.counter_size {
+		size = record.overrides.counter_size;
+	}
 	//
 	// Create the counter file.
 	//
@@ -665,15 +664,20 @@ fn generate_mini_concealment_svg_counter(record: &mut Record) {
 		Ok(counter_file) => counter_file,
 	};
 
-	generate_counter_header_svg_elements("vasl_multiman_counters", &counter_file, size, &record.piece, &"".to_string(), &record.comments, &record.version);
-
-	write!(counter_file, "\t<svg width=\"{0:.2}\" height=\"{0:.2}\" viewBox=\"0 0 48 48\">\n", mini_size).unwrap();
+	add_manifest_entry(&path, &record.piece, &record.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
 
-	generate_counter_background_svg(&counter_file, mini_size, &record.colors, &record.overrides);
+	generate_counter_header_svg_elements("vasl_multiman_counters", &counter_file, size, &record.piece, &"".to_string(), &record.comments, &record.version, &record.metadata());
 
-	write!(counter_file, "\t</svg>\n").unwrap();
+	if background_size != size {
+		write!(counter_file, "\t<svg width=\"{0:.2}\" height=\"{0:.2}\" viewBox=\"0 0 {1} {1}\">\n", background_size, size).unwrap();
+		generate_counter_background_svg(&counter_file, background_size, &record.colors, &record.overrides);
+		write!(counter_file, "\t</svg>\n").unwrap();
+	} else {
+		generate_counter_background_svg(&counter_file, size, &record.colors, &record.overrides);
+		generate_debug_working_area_svg(&counter_file);
+	}
 
-	generate_mini_concealment_counter_svg_elements(&counter_file, record);
+	generate_informational_counter_svg_elements(&counter_file, record, template);
 	generate_footer_svg(&counter_file);
 
 	drop(counter_file);
@@ -690,39 +694,30 @@ fn generate_broken_counter_svg_elements(counter_file: &std::fs::File, record: &m
 	record.generate_broken_morale_svg_elements(&counter_file);
 }
 
-fn generate_cloaking_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record) {
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"65.00%\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:25.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">?</text>\n", record.colors.text).unwrap();
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"44.00\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:8.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">Cloak</text>\n", record.colors.text).unwrap();
-}
-
-fn generate_concealment_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record) {
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"65.00%\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:25.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">?</text>\n", record.colors.text).unwrap();
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"44.00\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:8.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">7 morale</text>\n", record.colors.text).unwrap();
-}
-
-fn generate_large_concealment_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record) {
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"60.00%\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:30.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">?</text>\n", record.colors.text).unwrap();
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"55.00\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:12.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">7 morale</text>\n", record.colors.text).unwrap();
-}
+fn generate_informational_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record, template: &InformationalTemplateRecord) {
+	if !template.glyph.is_empty() {
+		write!(counter_file, "\t<text x=\"{0}\" y=\"{1}\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:{2:.2}px;font-weight:bold;font-family:{FONT_MAIN};fill:{3}\">{4}</text>\n", template.glyph_x, template.glyph_y, template.glyph_font_size.parse::<f64>().unwrap_or(25.0), record.colors.text, template.glyph).unwrap();
+	}
 
-fn generate_mini_concealment_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record) {
-	write!(counter_file, "\t<text x=\"25.00%\" y=\"35.00%\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:12.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">?</text>\n", record.colors.text).unwrap();
-}
+	if !template.caption.is_empty() {
+		let morale: String = if 0 != record.morale { record.morale.to_string() } else { template.morale.to_string() };
+		let caption: String = convert_text(&template.caption, COPY_FIELD, &morale);
 
-fn generate_roi_counter_svg_elements(mut counter_file: &std::fs::File, record: &mut Record) {
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"65.00%\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:25.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">?</text>\n", record.colors.text).unwrap();
-	write!(counter_file, "\t<text x=\"50.00%\" y=\"44.00\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:8.00px;font-weight:bold;font-family:Nimbus Sans L;fill:{0}\">No ROI</text>\n", record.colors.text).unwrap();
+		write!(counter_file, "\t<text x=\"{0}\" y=\"{1}\" dominant-baseline=\"auto\" text-anchor=\"middle\" style=\"font-size:{2:.2}px;font-weight:bold;font-family:{FONT_MAIN};fill:{3}\">{4}</text>\n", template.caption_x, template.caption_y, template.caption_font_size.parse::<f64>().unwrap_or(8.0), record.colors.text, caption).unwrap();
+	}
 }
 
-fn generate_svg_counter_announcer(record: &mut Record) {
+fn generate_svg_counter_announcer(record: &mut Record, informational_templates: &Vec<InformationalTemplateRecord>) {
 	if !record.args.quiet {
 		print!("Generating '{0}.svg' ...", record.piece);
 	} else {
 		println!("{0}", record.piece);
 	}
 	
-	if ClassIdentifier::MiniConcealment == record.class {
-		generate_mini_concealment_svg_counter(record);
+	if ClassIdentifier::Informational == record.class {
+		let template = select_informational_template(informational_templates, &record.informational, &record.nationality);
+
+		generate_informational_svg_counter(record, template);
 	} else {
 		generate_svg_counter(record);
 	}
@@ -768,7 +763,7 @@ impl SpreadsheetRecord {
 		result.nationality = if nat.is_empty() { self.nationality.to_string() } else { nat.to_string() };
 		
 		result.args = args.clone();
-		result.args.destination = format!("{0}{1}/", result.args.destination, result.nationality);
+		result.args.destination = construct_path(&result.nationality, "inf", &result.args.destination);
 		
 		result.overrides.sanitize(&self.overrides);
 		
@@ -812,36 +807,109 @@ impl SpreadsheetRecord {
 	}
 }
 
+//
+// One informational counter layout, "nationality" is empty for the default layout of a "class".
+//
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "lowercase")]
+struct InformationalTemplateRecord {
+	class: String,
+	nationality: String,
+	counter_size: String,
+	background_size: String,
+	glyph: String,
+	glyph_x: String,
+	glyph_y: String,
+	glyph_font_size: String,
+	caption: String,
+	caption_x: String,
+	caption_y: String,
+	caption_font_size: String,
+	morale: String,
+	comments: String,
+}
+
+fn read_informational_templates() -> Result<Vec<InformationalTemplateRecord>, Box<dyn Error>> {
+	let mut result: Vec<InformationalTemplateRecord> = Default::default();
+	let file = match File::open(INFORMATIONAL_TEMPLATES_FILE) {
+		Err(why) => panic!("couldn't open file: {0} {1}", INFORMATIONAL_TEMPLATES_FILE, why),
+		Ok(file) => file,
+	};
+	let mut rdr = csv::Reader::from_reader(file);
+	
+	for template in rdr.deserialize() {
+		result.push(template?);
+	}
+	
+	return Ok(result);
+}
+//
+// A nationality-specific layout wins over the default one.
+//
+fn select_informational_template<'a>(informational_templates: &'a Vec<InformationalTemplateRecord>, class: &String, nationality: &String) -> &'a InformationalTemplateRecord {
+	let mut result: Option<&InformationalTemplateRecord> = None;
+	
+	for template in informational_templates {
+		if *class == template.class {
+			if *nationality == template.nationality {
+				return template;
+			} else if template.nationality.is_empty() {
+				result = Some(template);
+			}
+		}
+	}
+	
+	match result {
+		Some(template) => template,
+		None => panic!("select_informational_template() - no '{0}' template for '{1}' in {2}!", class, nationality, INFORMATIONAL_TEMPLATES_FILE),
+	}
+}
+
 fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
 	
+	let informational_templates = read_informational_templates()?;
+	let manifest: ManifestWriter = ManifestWriter::new("inf", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
+
+		set_manifest_source_row(&row);
 
 		if !spreadsheet_record.nationality.is_empty() {
 			let mut record: Record = spreadsheet_record.sanitize(&"".to_string(), &args);
 			let pieces = record.pieces.clone();
 			
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.piece = piece.to_string();
-					generate_svg_counter_announcer(&mut record);
+					generate_svg_counter_announcer(&mut record, &informational_templates);
 				} else {
-					let (piece, nationality) = piece.split_once("@").unwrap();
+					let (piece, nationality) = split_alternate_nationality(&piece);
 					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					
 					alt_record.colors = nationality_to_colors(&nationality.to_string());
 					alt_record.piece = piece.to_string();
 					
-					generate_svg_counter_announcer(&mut alt_record);
+					generate_svg_counter_announcer(&mut alt_record, &informational_templates);
 				}
 			}			
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
@@ -16,16 +16,23 @@ use common_functions::colors::*;
 use common_functions::common_record::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
 use common_functions::malfunction::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
 use common_functions::movement::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
-// TODO: CREATE_MALF_SIDE NOT YET? use common_functions::special::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
+use common_functions::special::*;
 use common_functions::text_field::*;
 use common_functions::turret::*;
 use common_functions::utils::*;
 
 pub const GT_FONT_SIZE: f64 =		11.0;
-pub const LIMBERED_FONT_SIZE: f64 =	 7.0;
 
 #[derive(Default)]
 struct Record {
@@ -33,15 +40,31 @@ struct Record {
 	common: CommonRecord,
 	gun_type: TextField,
 	movement: OrdnanceMovementValues,
-	// TODO: NOT YET? special: Special, // Details for malfunction side.
+	special: Special,
 	repair_or_disable: RepairValues,
 	limbered: bool,
 	limbered_data: String,
+	limbered_piece: String,
 }
 
 impl Record {
+	//
+	// NOVR_IMAGE only replaces the gun's own depiction, the limbered counter keeps the limbered piece's.
+	//
+	fn depiction(&self, unit_depiction: &String) -> String {
+		if self.limbered {
+			return unit_depiction.to_string();
+		}
+
+		return self.common.overrides.depiction(unit_depiction);
+	}
+
 	fn reinitialize_limbered_data(&mut self) {
 		self.limbered = true;
+		self.limbered_piece = LIMBERED_PIECE_DEFAULT.to_string();
+		self.special.set_limbered(&self.common.ma.color);
+
+		self.validate_limbered_data();
 
 		if self.limbered_data.contains(LIMBERED_NO_FIRE) {
 			self.common.turret.speed = TurretType::NonTurreted;
@@ -59,7 +82,7 @@ impl Record {
 		}
 		
 		for entry in entries {
-			let mut temp: String;
+			let temp: String;
 		
 			if entry.contains(NOVR_MA) {
 				self.common.overrides.ma.text = extract_from(&entry, NOVR_MA);
@@ -67,6 +90,10 @@ impl Record {
 			} else if entry.contains(NOVR_MB) {
 				self.common.overrides.ma.malf.text = extract_from(&entry, NOVR_MB);
 				self.common.malfunction.sanitize(&"".to_string(), &self.common.overrides.ma, &self.common.colors);
+			} else if entry.contains(LIMBERED_PIECE) {
+				self.limbered_piece = extract_from(&entry, LIMBERED_PIECE);
+			} else if entry.contains(LIMBERED_REPAIR) {
+				self.repair_or_disable = sanitize_repair_numbers(&extract_from(&entry, LIMBERED_REPAIR), &"".to_string(), &self.common.colors);
 			} else if entry.contains(NOVR_GT) {
 				temp = extract_from(&entry, NOVR_GT);
 		
@@ -77,18 +104,7 @@ impl Record {
 				temp = extract_from(&entry, NOVR_SIZE);
 		
 				if !temp.is_empty() {
-					self.movement.target_size = temp.parse::<i64>().unwrap_or(0);
-		
-					if 0 < self.movement.target_size {
-						self.movement.manhandling_number.color = BLACK.to_string();
-						self.movement.unhooking_penalty_color = self.common.colors.unhooking_penalty_color.to_string();
-					} else if 0 == self.movement.target_size {
-						self.movement.manhandling_number.color = self.common.colors.manhandling_fill.to_string();
-						self.movement.unhooking_penalty_color = self.common.colors.unhooking_penalty_color.to_string();
-					} else {
-						self.movement.manhandling_number.color = RED.to_string();
-						self.movement.unhooking_penalty_color = RED.to_string();
-					}
+					self.movement.set_target_size(temp.parse::<i64>().unwrap_or(0), &self.common.colors);
 				}
 			} else if entry.contains(NOVR_MOUNT) {
 				temp = extract_from(&entry, NOVR_MOUNT);
@@ -107,24 +123,33 @@ impl Record {
 				temp = extract_from(&entry, NOVR_MANHANDLING);
 		
 				if !temp.is_empty() {
-					if temp.contains(FIVE_LOBED_ASTERISK) {
-						self.movement.manhandling_number.note.action = NoteAction::Infix;
-						self.movement.manhandling_number.note.text = FIVE_LOBED_ASTERISK_UC.to_string();
-						temp = strip_all_occurances(&temp.to_string(), FIVE_LOBED_ASTERISK);
-					}
-		
-					self.movement.manhandling_number.text = temp;
-		
-					if self.movement.manhandling_number.text.contains("<b>") {
-						self.movement.manhandling_number.text = extract_string(&self.movement.manhandling_number.text, &String::from("</b>"), &String::from("<b>"));
-						self.movement.unhooking_penalty = true;
-					} else {
-						self.movement.unhooking_penalty = false;
-					}						
+					self.movement.override_manhandling_number(&temp);
 				}
 			}
 		}
 	}
+	//
+	// Only the overrides that make sense for a limbered gun are allowed, and "no_fire" can't be mixed with the ones that describe firing.
+	//
+	fn validate_limbered_data(&self) {
+		let legal: [&str; 12] = [LIMBERED_NO_FIRE, LIMBERED_PIECE, LIMBERED_REPAIR, NOVR_GT, NOVR_IFE, NOVR_MA, NOVR_MANHANDLING, NOVR_MB, NOVR_MOUNT, NOVR_RANGE, NOVR_ROF, NOVR_SIZE];
+		let firing: [&str; 7] = [NOVR_GT, NOVR_IFE, NOVR_MA, NOVR_MB, NOVR_MOUNT, NOVR_RANGE, NOVR_ROF];
+		let no_fire: bool = self.limbered_data.contains(LIMBERED_NO_FIRE);
+	
+		for entry in extract_vector(&self.limbered_data, OVERRIDE_DELIMITER) {
+			if entry.is_empty() {
+				continue;
+			}
+	
+			if !legal.iter().any(|l| entry.starts_with(l)) {
+				panic!("Record::validate_limbered_data()@{0}: illegal limbered override '{1}' for '{2}'", line!(), entry, self.common.piece_front);
+			}
+	
+			if no_fire && firing.iter().any(|f| entry.starts_with(f)) {
+				panic!("Record::validate_limbered_data()@{0}: limbered override '{1}' conflicts with '{2}' for '{3}'", line!(), entry, LIMBERED_NO_FIRE, self.common.piece_front);
+			}
+		}
+	}
 }
 
 fn generate_gun_type(mut output: &std::fs::File, gun_type: &TextField, overrides: &Overrides) {
@@ -141,14 +166,14 @@ fn generate_gun_type(mut output: &std::fs::File, gun_type: &TextField, overrides
 	}
 }
 
-fn generate_counter_front(mut output: &std::fs::File, path: &String, unit_depiction: &String, record: &Record) {
+fn generate_counter_front(output: &std::fs::File, path: &String, unit_depiction: &String, record: &Record) {
 	generate_counter_background_svg(output, 60, &record.common.colors, &record.common.overrides);
 	
 	if !record.common.overrides.ma.ignore {
 		record.common.turret.generate_svg_elements(&output);
 	}
 	
-	generate_unit_depiction_svg(output, &path, &unit_depiction, &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
+	generate_unit_depiction_svg(output, &path, &record.depiction(unit_depiction), &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
 	generate_debug_working_area_svg(&output);
 
 	if !record.common.overrides.ma.ignore {
@@ -159,7 +184,7 @@ fn generate_counter_front(mut output: &std::fs::File, path: &String, unit_depict
 		y_position -= generate_malfunction_elements(&output, &record.common.malfunction, y_position);
 
 		if !record.common.ma.rof.text.is_empty() {
-			generate_rof_element(&output, &record.common.ma.rof, y_position, &record.common.ma.color, false);
+			generate_rof_element(&output, &record.common.ma.rof, y_position, &record.common.ma.color, false, false);
 		}
 
 		generate_range_and_special_ammunition_elements(&output, &record.common.ma);
@@ -175,9 +200,7 @@ fn generate_counter_front(mut output: &std::fs::File, path: &String, unit_depict
 		generate_manhandling_number_for_counter_front(&output, &record.movement);
 	}
 
-	if record.limbered {
-		write!(output, "\t<text x=\"{0}\" y=\"8.00\" text-anchor=\"start\" style=\"font-size:{1:.2}px;{FONT_WEIGHT_NORM};font-family:{2};fill:{3}\">Limbered</text>\n", GUN_COLUMN_X_POSITION, LIMBERED_FONT_SIZE, FONT_MAIN, record.common.ma.color).unwrap();
-	}
+	record.special.generate_svg(output, true);	// Handle all the "special" text including "Limbered".
 }
 
 fn generate_counter_back(mut output: &std::fs::File, path: &String, unit_depiction: &String, record: &Record) {
@@ -194,27 +217,13 @@ fn generate_counter_back(mut output: &std::fs::File, path: &String, unit_depicti
 		write!(output, "\t<text x=\"57.00\" y=\"57.00\" text-anchor=\"end\" style=\"font-size:8pt;font-family:{0};fill:{1}\">X{2}</text>\n", FONT_MAIN, record.repair_or_disable.disable.color, record.repair_or_disable.disable.text).unwrap();
 	}
 
-	// TODO not yet? record.special.generate_svg(output);	// Handle all the "special" text including "Limbered".
+	record.special.generate_svg(output, false);	// Handle all the "special" text including "Limbered".
 
 	if 0 != record.movement.manhandling_number.text.len() {
 		generate_manhandling_number_for_counter_back(&output, &record.movement);
 	}
 	
-	if record.limbered {
-		write!(output, "\t<text x=\"57.00\" y=\"8.00\" text-anchor=\"end\" style=\"font-size:{0:.2}px;{FONT_WEIGHT_NORM};font-family:{1};fill:{2}\">Limbered</text>\n", LIMBERED_FONT_SIZE, FONT_MAIN, record.common.ma.color).unwrap();
-	}
-	
-	generate_unit_depiction_svg(output, &path, &unit_depiction, &record.common.note, &record.common.svg_image_transform, false, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
-}
-
-fn extract_nationality(source: &String, nationality: &String) -> std::string::String {
-	let mut result = (&source[0..2]).to_string();
-
-	if !nationality.is_empty() {
-		result = nationality.to_string();
-	}
-
-	return result;
+	generate_unit_depiction_svg(output, &path, &record.depiction(unit_depiction), &record.common.note, &record.common.svg_image_transform, false, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
 }
 
 fn generate_counters(record: &Record) {
@@ -223,7 +232,7 @@ fn generate_counters(record: &Record) {
 	let mut name = record.common.name.clone();
 
 	if record.limbered {
-		piece.push_str("-l");
+		piece = convert_text(&record.limbered_piece, COPY_FIELD, &record.common.piece_front);
 		name.push_str(" (Limbered)");
 	}
 
@@ -243,7 +252,9 @@ fn generate_counters(record: &Record) {
 		Ok(output) => output,
 	};
 
-	generate_counter_header_svg_elements("vasl_ordnance_counters", &output, 60, &name, &record.common.note, &record.common.comments, &record.common.version);
+	add_manifest_entry(&path, &piece, &record.common.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
+
+	generate_counter_header_svg_elements("vasl_ordnance_counters", &output, 60, &name, &record.common.note, &record.common.comments, &record.common.version, &record.common.metadata.for_piece(&piece));
 	generate_counter_front(&output, &path, &unit_depiction, &record);
 	generate_footer_svg(&output);
 
@@ -253,17 +264,16 @@ fn generate_counters(record: &Record) {
 		//
 		// Create the back counter file.
 		//
-		piece = record.common.piece_front.clone();
-
-		if record.limbered {
-			piece.push_str("-l");
-			name.push_str(" (Limbered)");
-		}
-
+		piece = unit_depiction.clone();
 		piece.push_str("b");
 		
 		name = record.common.name.clone();
-		name.push_str(" (Malfunctioned)");
+
+		if record.limbered {
+			name.push_str(" (Limbered, Malfunctioned)");
+		} else {
+			name.push_str(" (Malfunctioned)");
+		}
 
 		if !record.args.quiet {
 			print!("Generating '{0}.svg' ({1}) ...", piece, record.common.note);
@@ -276,7 +286,9 @@ fn generate_counters(record: &Record) {
 			Ok(output) => output,
 		};
 
-		generate_counter_header_svg_elements("vasl_gun_counters", &output, 60, &name, &record.common.note, &record.common.comments, &record.common.version);
+		add_manifest_entry(&path, &piece, &record.common.nationality, MANIFEST_BACK, MANIFEST_GENERATED);
+
+		generate_counter_header_svg_elements("vasl_gun_counters", &output, 60, &name, &record.common.note, &record.common.comments, &record.common.version, &record.common.metadata.for_piece(&piece));
 		generate_counter_back(&output, &path, &unit_depiction, &record);
 		generate_footer_svg(&output);
 
@@ -288,7 +300,7 @@ fn generate_counters(record: &Record) {
 	}
 }
 
-pub fn sanitize_repair_numbers(source: &String, /* TODO: CREATE_MALF_SIDE NOT YET? special_repair: &String,*/ colors: &Colors) -> RepairValues {
+pub fn sanitize_repair_numbers(source: &String, special_repair: &String, colors: &Colors) -> RepairValues {
 	let mut result: RepairValues = Default::default();
 	let mut repair: &str = "1";
 	let mut disable: &str = "6";
@@ -297,11 +309,11 @@ pub fn sanitize_repair_numbers(source: &String, /* TODO: CREATE_MALF_SIDE NOT YE
 		(repair, disable) = source.split_once('/').unwrap();
 	}
 
-	// TODO: NOT YET? if !special_repair.is_empty() {
-	// TODO: NOT YET? 	result.repair.text = special_repair.to_string();
-	// TODO: NOT YET? } else {
+	if !special_repair.is_empty() {
+		result.repair.text = special_repair.to_string();
+	} else {
 		result.repair.text = repair.to_string();
-	// TODO: NOT YET? }
+	}
 
 	result.repair.color = colors.text.to_string();
 
@@ -348,7 +360,13 @@ struct SpreadsheetRecord {
 impl SpreadsheetRecord {
 	fn sanitize(&mut self, nat: &String, args: &Arguments) -> Record {
 		let mut result: Record = Default::default();
-		let mut nationality = if nat.is_empty() { extract_nationality(&self.piece, &result.common.overrides.nationality) } else { nat.to_string() };
+		let mut nationality: String = Default::default();
+		
+		if !nat.is_empty() {
+			nationality = nat.to_string();
+		} else if !self.overrides.contains(NOVR_NATIONALITY) {
+			nationality = nationality_from_piece(&self.piece);
+		}
 		
 		if self.overrides.contains(NOVR_NATIONALITY) {
 			nationality = extract_from(&self.overrides, NOVR_NATIONALITY);
@@ -360,7 +378,9 @@ impl SpreadsheetRecord {
 		
 		result.common.initialize(&nationality, &self.notes, &self.name, &self.caliber, &self.range, &self.rof_ife, &self.breakdown, &self.version, &self.piece, &self.svg_image_transform, &self.comments);
 		
-		result.common.turret = sanitize_mount(&self.special, &result.common.overrides, &result.common.colors);
+		result.common.metadata = CounterMetadata::new("ordnance", &nationality);
+		result.common.metadata.set_chapter_h_values(&self.notes, &self.bpv, &self.rf, &self.dates);
+		result.common.metadata.count = strip_html_tags(&self.count);
 		
 		if !result.common.overrides.special_ammo.is_empty() {
 			result.common.ma.special_ammo.sanitize(&"".to_string(), &result.common.overrides.special_ammo, RANGE_FONTS, &result.common.colors);
@@ -370,9 +390,11 @@ impl SpreadsheetRecord {
 		
 		result.movement.sanitize(&self.manhandling, strip_all_occurances(&self.target_size, DAGGER).parse::<i64>().unwrap_or(0), &self.special, &result.common.overrides, &result.common.colors);
 		
-		// TODO: CREATE_MALF_SIDE NOT YET? result.special.initialize(&self.special, &result.common.overrides, result.limbered, &result.common.colors);
+		result.special.initialize(&self.special, &result.common.overrides, &result.common.colors);
+		
+		result.common.turret = sanitize_mount(&result.special.mount, &result.common.overrides, &result.common.colors);
 	
-		result.repair_or_disable = sanitize_repair_numbers(&self.r_x, /* TODO: CREATE_MALF_SIDE NOT YET? &result.special.repair,*/ &result.common.colors);
+		result.repair_or_disable = sanitize_repair_numbers(&self.r_x, &result.special.repair, &result.common.colors);
 	
 		result.limbered_data = self.limbered.clone();
 	
@@ -384,11 +406,18 @@ fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
 		
+	let manifest: ManifestWriter = ManifestWriter::new("gun", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
+
+		set_manifest_source_row(&row);
 
 		if NOVR_ANNOUNCE == spreadsheet_record.overrides {
 			if !args.quiet {
@@ -400,7 +429,7 @@ fn run() -> Result<(), Box<dyn Error>> {
 			let pieces = record.common.pieces.clone();
 
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.common.piece_front = piece.to_string();
 					generate_counters(&record);
 					
@@ -411,7 +440,7 @@ fn run() -> Result<(), Box<dyn Error>> {
 						generate_counters(&limbered_record);
 					}
 				} else {
-					let (piece_name, nationality) = piece.split_once("@").unwrap();
+					let (piece_name, nationality) = split_alternate_nationality(&piece);
 					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					let mut alt_limbered_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					
@@ -434,6 +463,13 @@ fn run() -> Result<(), Box<dyn Error>> {
 			}
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+	check_special_errors()?;
+
 	Ok(())
 }
 
@@ -0,0 +1,262 @@
+use std::{error::Error, fs, process};
+use std::collections::BTreeMap;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::build_file::*;
+use common_functions::manifest::*;
+use common_functions::overrides::*;
+use common_functions::utils::*;
+
+pub const GALLERY_DIRECTORY: &str =	"review/";	// Written to the destination, "index.html" and one page per nationality and category.
+pub const GALLERY_INDEX: &str =		"index.html";
+
+const OVERRIDES_COLUMN: &str =		"overrides";
+const GALLERY_SCALE: u32 =			2;			// Counters are shown at 200%.
+//
+// Plain CSS (no scripts) so that the pages open from disk, the details of a counter show when the mouse is over it. The <object>
+// doesn't take the mouse so that its figure does (an <img> would do too, but doesn't draw the SVG's linked depictions).
+//
+const GALLERY_STYLE: &str = "
+	body { font-family: sans-serif; font-size: 13px; background: #f0f0f0; }
+	h1 { font-size: 20px; }
+	a { color: #204a87; }
+	.counter { display: inline-block; vertical-align: top; margin: 4px; padding: 6px; background: #ffffff; border: 1px solid #d0d0d0; }
+	.counter h2 { margin: 0 0 4px 0; font-size: 13px; }
+	figure { display: inline-block; position: relative; vertical-align: top; margin: 0 2px; text-align: center; }
+	figure object { pointer-events: none; transform-origin: 0 0; }
+	.zoom { display: block; overflow: hidden; }
+	figcaption { color: #808080; font-size: 11px; }
+	.details { display: none; position: absolute; left: 0; top: 100%; z-index: 1; min-width: 320px; padding: 6px; text-align: left; background: #ffffe0; border: 1px solid #808080; box-shadow: 2px 2px 6px #808080; }
+	figure:hover .details { display: block; }
+	.details table { border-collapse: collapse; }
+	.details th, .details td { padding: 1px 6px 1px 0; vertical-align: top; font-size: 11px; text-align: left; }
+	.details th { color: #505050; white-space: nowrap; }
+	.details h3 { margin: 4px 0 2px 0; font-size: 11px; }
+";
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct GalleryArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long, default_value = "")]
+	legacy_images: String,	// The VASL images folder with the legacy GIFs (defaults to the destination).
+}
+//
+// A data file's header and its rows, keyed by the line they start on (the manifest's "line").
+//
+struct SourceRows {
+	header: Vec<String>,
+	rows: BTreeMap<u64, Vec<String>>,
+}
+
+fn read_source(path: &String) -> Option<SourceRows> {
+	let mut rdr = csv::ReaderBuilder::new().flexible(true).from_path(path).ok()?;
+	let header: Vec<String> = rdr.headers().ok()?.iter().map(|column| column.to_string()).collect();
+	let mut rows: BTreeMap<u64, Vec<String>> = Default::default();
+
+	for row in rdr.records() {
+		let row = match row {
+			Err(why) => {
+				println!("{path}: skipped a row: {why}");	// Its counters show "Row not found".
+				continue;
+			}
+			Ok(row) => row,
+		};
+
+		rows.insert(row.position().unwrap().line(), row.iter().map(|value| value.to_string()).collect());
+	}
+
+	return Some(SourceRows {
+		header,
+		rows,
+	});
+}
+//
+// Outputs are relative to the destination, the pages are one folder down.
+//
+fn page_href(output: &String) -> String {
+	return format!("../{0}", escape_href(output));
+}
+
+fn escape_href(path: &String) -> String {
+	return path.replace('%', "%25").replace(' ', "%20").replace('#', "%23");
+}
+//
+// The legacy GIFs of "--legacy-images" aren't in the destination, they're referred to by their absolute path.
+//
+fn legacy_href(args: &GalleryArguments, output: &String, path: &String) -> String {
+	if args.legacy_images.is_empty() {
+		return page_href(&legacy_image(output));
+	}
+
+	let absolute: String = fs::canonicalize(path).map(|path| path.to_string_lossy().to_string()).unwrap_or(path.to_string());
+
+	return format!("file://{0}", escape_href(&absolute));
+}
+//
+// "name=35-S <i>MT</i>|copy" becomes ("name", "35-S <i>MT</i>") and ("copy", ""), in the order given.
+//
+fn parse_overrides(overrides: &String) -> Vec<(String, String)> {
+	if overrides.trim().is_empty() {
+		return Default::default();
+	}
+
+	return extract_vector(overrides, OVERRIDE_DELIMITER).iter().filter(|entry| !entry.is_empty()).map(|entry| match entry.split_once('=') {
+		Some((key, value)) => (key.to_string(), value.to_string()),
+		None => (entry.to_string(), "".to_string()),
+	}).collect();
+}
+//
+// What the counter was made from: where it's written, its data file row (the non-empty columns) and its overrides.
+//
+fn counter_details(entry: &ManifestEntry, sources: &mut BTreeMap<String, Option<SourceRows>>) -> String {
+	let mut result: String = "<div class=\"details\"><table>".to_string();
+	let source: &Option<SourceRows> = sources.entry(entry.source.to_string()).or_insert_with(|| read_source(&entry.source));
+
+	result.push_str(&format!("<tr><th>output</th><td>{0}</td></tr>", escape_xml_attribute(&entry.output)));
+	result.push_str(&format!("<tr><th>source</th><td>{0}, line {1} ({2})</td></tr>", escape_xml_attribute(&entry.source), entry.line, entry.action));
+	result.push_str("</table>");
+
+	let Some(row) = source.as_ref().and_then(|source| source.rows.get(&entry.line).map(|row| (&source.header, row))) else {
+		result.push_str("<h3>Row not found, run from the folder the data files are in</h3></div>");
+		return result;
+	};
+	let (header, row) = row;
+	let mut overrides: Vec<(String, String)> = Default::default();
+
+	result.push_str("<h3>Row</h3><table>");
+
+	for (column, value) in header.iter().zip(row.iter()) {
+		if value.trim().is_empty() {
+			continue;
+		}
+
+		if OVERRIDES_COLUMN == column {
+			overrides = parse_overrides(value);
+		}
+
+		result.push_str(&format!("<tr><th>{0}</th><td>{1}</td></tr>", escape_xml_attribute(column), escape_xml_attribute(value)));
+	}
+
+	result.push_str("</table>");
+
+	if !overrides.is_empty() {
+		result.push_str("<h3>Overrides</h3><table>");
+
+		for (key, value) in &overrides {
+			result.push_str(&format!("<tr><th>{0}</th><td>{1}</td></tr>", escape_xml_attribute(key), escape_xml_attribute(value)));
+		}
+
+		result.push_str("</table>");
+	}
+
+	result.push_str("</div>");
+
+	return result;
+}
+
+//
+// The counter as VASL will show it (a generated SVG) and the legacy GIF it replaces, when there's one. The counters have no
+// viewBox, so they're scaled by CSS rather than by the size of the <object>.
+//
+fn counter_figures(args: &GalleryArguments, entry: &ManifestEntry, side: &str, sources: &mut BTreeMap<String, Option<SourceRows>>) -> String {
+	let counter: u32 = counter_size(&format!("{0}{1}", args.outputs.destination, entry.output));
+	let size: u32 = counter * GALLERY_SCALE;
+	let details: String = counter_details(entry, sources);
+	let mut result: String = Default::default();
+
+	result.push_str(&format!("\t\t<figure><span class=\"zoom\" style=\"width:{size}px;height:{size}px\"><object type=\"image/svg+xml\" data=\"{0}\" width=\"{counter}\" height=\"{counter}\" style=\"transform:scale({GALLERY_SCALE})\"></object></span><figcaption>{side}</figcaption>{details}</figure>\n", page_href(&entry.output)));
+
+	if let Some(legacy) = legacy_image_path(&args.outputs, &args.legacy_images, &entry.output) {
+		result.push_str(&format!("\t\t<figure><img src=\"{0}\" height=\"{size}\" alt=\"{1}\"><figcaption>{side} ({LEGACY_EXTENSION})</figcaption>{details}</figure>\n", legacy_href(args, &entry.output, &legacy), escape_xml_attribute(&entry.piece)));
+	}
+
+	return result;
+}
+
+fn page_start(title: &String) -> String {
+	let mut result: String = Default::default();
+
+	result.push_str("<!DOCTYPE html>\n");
+	result.push_str("<html lang=\"en\">\n");
+	result.push_str("<head>\n");
+	result.push_str("<meta charset=\"utf-8\">\n");
+	result.push_str(&format!("<title>{0}</title>\n", escape_xml_attribute(title)));
+	result.push_str(&format!("<style>{GALLERY_STYLE}</style>\n"));
+	result.push_str("</head>\n");
+	result.push_str("<body>\n");
+	result.push_str(&format!("<h1>{0}</h1>\n", escape_xml_attribute(title)));
+
+	return result;
+}
+//
+// One page per nationality and category: each counter's front, its back (if it has one) and their legacy GIFs.
+//
+fn gallery_page(args: &GalleryArguments, nationality: &String, category: &String, counters: &Vec<(&ManifestEntry, Option<&ManifestEntry>)>, sources: &mut BTreeMap<String, Option<SourceRows>>) -> String {
+	let mut result: String = page_start(&format!("{nationality} {category} counters"));
+
+	result.push_str(&format!("<p><a href=\"{GALLERY_INDEX}\">All nationalities</a> - {0} counter(s), hover over a counter for its data.</p>\n", counters.len()));
+
+	for (front, back) in counters {
+		result.push_str(&format!("<div class=\"counter\">\n\t<h2>{0}</h2>\n\t<div>\n", escape_xml_attribute(&front.piece)));
+		result.push_str(&counter_figures(args, front, MANIFEST_FRONT, sources));
+
+		if let Some(back) = back {
+			result.push_str(&counter_figures(args, back, MANIFEST_BACK, sources));
+		}
+
+		result.push_str("\t</div>\n</div>\n");
+	}
+
+	result.push_str("</body>\n</html>\n");
+
+	return result;
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = GalleryArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let pages: BTreeMap<(String, String), Vec<&ManifestEntry>> = group_manifest_entries(&entries, &args.outputs, &args.selection)?;
+	let mut sources: BTreeMap<String, Option<SourceRows>> = Default::default();
+	let directory: String = format!("{0}{GALLERY_DIRECTORY}", args.outputs.destination);
+
+	fs::create_dir_all(&directory)?;
+
+	let mut index: String = page_start(&"Counter review".to_string());
+
+	index.push_str("<ul>\n");
+
+	for ((nationality, category), entries) in &pages {
+		let counters: Vec<(&ManifestEntry, Option<&ManifestEntry>)> = pair_fronts_and_backs(entries);
+		let name: String = format!("{nationality}_{category}.html");
+
+		write_page(&format!("{directory}{name}"), &gallery_page(&args, nationality, category, &counters, &mut sources), &args.outputs);
+		index.push_str(&format!("\t<li><a href=\"{name}\">{nationality} {category}</a> ({0} counter(s))</li>\n", counters.len()));
+	}
+
+	index.push_str("</ul>\n</body>\n</html>\n");
+	write_page(&format!("{directory}{GALLERY_INDEX}"), &index, &args.outputs);
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
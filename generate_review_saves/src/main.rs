@@ -0,0 +1,330 @@
+use std::{error::Error, fs, process};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::write::SimpleFileOptions;
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::build_file::*;
+use common_functions::manifest::*;
+
+pub const REVIEW_SAVES_DIRECTORY: &str =	"./review/SVG 1.0 Tests/";	// "<nationality>_<category>.vsav", loaded in VASL to review the counters.
+pub const REVIEW_SAVE_TEMPLATE: &str =		"ge_veh.vsav";				// Boards and settings for a nationality and category without a save yet.
+
+const SAVED_GAME_ENTRY: &str =		"savedGame";
+const SAVE_DATA_ENTRIES: [&str; 2] =	[ "savedata", "moduledata" ];	// Copied from the template as they are.
+const OBFUSCATION_HEADER: &str =	"!VCSK";
+const OBFUSCATION_KEY: u8 =			0xc2;		// VASSAL picks one at random, a fixed key keeps unchanged saves unchanged.
+const COMMAND_SEPARATOR: u8 =		0x1b;		// ESC
+const PIECE_ID_BASE: u64 =			1000000000000;	// Piece ids are numbers, the hand-made saves used the time they were created.
+//
+// Where the pieces go on the template's boards (the area the hand-made saves used), one piece every REVIEW_SPACING pixels.
+//
+const REVIEW_MAP: &str =			"Main Map";
+const REVIEW_GRID_LEFT: u32 =		456;
+const REVIEW_GRID_TOP: u32 =		346;
+const REVIEW_GRID_RIGHT: u32 =		1806;
+const REVIEW_GRID_BOTTOM: u32 =		2270;
+const REVIEW_SPACING: u32 =			64;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct SavesArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long)]
+	legacy: bool,		// Put the legacy GIF piece next to each counter.
+
+	#[arg(long, default_value = "")]
+	legacy_images: String,	// The VASL images folder with the legacy GIFs (defaults to the destination).
+
+	#[arg(long, default_value = "")]
+	saves: String,		// Defaults to REVIEW_SAVES_DIRECTORY.
+}
+//
+// The piece on the review map, with the traits it has in VASL's counters window (see build_file.rs).
+//
+fn add_piece_command(id: u64, piece: &PieceDefinition, x: u32, y: u32) -> String {
+	return piece.add_piece(&(PIECE_ID_BASE + id).to_string(), &[ REVIEW_MAP.to_string(), x.to_string(), y.to_string(), id.to_string(), "0".to_string() ]);
+}
+//
+// Saved games are written "!VCSK<key>" followed by each byte xor'ed with the key, in hexadecimal.
+//
+fn obfuscate(data: &Vec<u8>) -> String {
+	let mut result: String = format!("{OBFUSCATION_HEADER}{OBFUSCATION_KEY:02x}");
+
+	for byte in data {
+		result.push_str(&format!("{0:02x}", byte ^ OBFUSCATION_KEY));
+	}
+
+	return result;
+}
+
+fn deobfuscate(text: &String) -> Vec<u8> {
+	let Some(hex) = text.strip_prefix(OBFUSCATION_HEADER) else {
+		return text.as_bytes().to_vec();
+	};
+	let bytes: Vec<u8> = (0..hex.len() / 2).map(|n| u8::from_str_radix(&hex[2 * n..2 * n + 2], 16).unwrap_or(0)).collect();
+	let Some((key, data)) = bytes.split_first() else {
+		return Default::default();
+	};
+
+	return data.iter().map(|byte| byte ^ key).collect();
+}
+//
+// The template's saved game commands (boards, global properties, extensions ...) and its other entries.
+//
+struct Template {
+	commands: Vec<Vec<u8>>,
+	entries: Vec<(String, Vec<u8>)>,
+}
+
+fn read_template(path: &String) -> Result<Template, Box<dyn Error>> {
+	let mut archive = ZipArchive::new(File::open(path).map_err(|why| format!("couldn't open template '{path}': {why}"))?)?;
+	let mut saved_game: String = Default::default();
+	let mut entries: Vec<(String, Vec<u8>)> = Default::default();
+
+	archive.by_name(SAVED_GAME_ENTRY)?.read_to_string(&mut saved_game)?;
+
+	for name in SAVE_DATA_ENTRIES {
+		let mut data: Vec<u8> = Default::default();
+
+		if let Ok(mut entry) = archive.by_name(name) {
+			entry.read_to_end(&mut data)?;
+			entries.push((name.to_string(), data));
+		}
+	}
+
+	let commands: Vec<Vec<u8>> = deobfuscate(&saved_game).split(|byte| COMMAND_SEPARATOR == *byte).map(|command| command.to_vec()).collect();
+
+	return Ok(Template {
+		commands,
+		entries,
+	});
+}
+//
+// The name VASL shows for the piece, the counter's <title>.
+//
+fn piece_name(args: &SavesArguments, entry: &ManifestEntry) -> String {
+	let (title, _) = counter_title_and_size(&format!("{0}{1}", args.outputs.destination, entry.output));
+
+	return title.unwrap_or(entry.piece.to_string());
+}
+//
+// The pieces of a counter, side by side: the counter (its back is a layer, as in VASL) and, with "--legacy", the legacy GIFs of its
+// front and back when the images folder (or "--legacy-images") has them.
+//
+fn counter_pieces(args: &SavesArguments, front: &ManifestEntry, back: Option<&ManifestEntry>) -> Vec<PieceDefinition> {
+	let mut result: Vec<PieceDefinition> = vec![ PieceDefinition::counter(&args.outputs.destination, front, back) ];
+
+	for entry in [ Some(front), back ].into_iter().flatten() {
+		if args.legacy && legacy_image_path(&args.outputs, &args.legacy_images, &entry.output).is_some() {
+			result.push(PieceDefinition::image(&legacy_image(&entry.output), &format!("{0} ({LEGACY_EXTENSION})", piece_name(args, entry))));
+		}
+	}
+
+	return result;
+}
+//
+// Each counter's images go on one line of the grid, a counter that doesn't fit on what's left of the line starts the next one.
+//
+fn piece_commands(args: &SavesArguments, counters: &Vec<(&ManifestEntry, Option<&ManifestEntry>)>, save: &String) -> Vec<String> {
+	let mut result: Vec<String> = Default::default();
+	let columns: u32 = (REVIEW_GRID_RIGHT - REVIEW_GRID_LEFT) / REVIEW_SPACING + 1;
+	let (mut column, mut row): (u32, u32) = (0, 0);
+
+	for (front, back) in counters {
+		let pieces: Vec<PieceDefinition> = counter_pieces(args, front, *back);
+
+		if column + pieces.len() as u32 > columns && 0 != column {
+			column = 0;
+			row += 1;
+		}
+
+		for piece in &pieces {
+			let (x, y): (u32, u32) = (REVIEW_GRID_LEFT + column * REVIEW_SPACING, REVIEW_GRID_TOP + row * REVIEW_SPACING);
+
+			result.push(add_piece_command(result.len() as u64 + 1, piece, x, y));
+			column += 1;
+		}
+	}
+
+	if REVIEW_GRID_TOP + row * REVIEW_SPACING > REVIEW_GRID_BOTTOM {
+		println!("{save}: {0} rows of pieces, the last ones are below the boards", row + 1);
+	}
+
+	return result;
+}
+
+//
+// The id of the piece (or stack) the template's command adds, "+/<id>/<type>/<state>".
+//
+fn added_piece_id(command: &Vec<u8>) -> Option<String> {
+	let text: String = String::from_utf8_lossy(command).to_string();
+
+	return text.strip_prefix(&format!("{ADD_PIECE}/")).and_then(|rest| rest.split_once('/')).map(|(id, _)| id.to_string());
+}
+//
+// Whether the template's command adds a piece showing one of the generated (or copied) counters, or their legacy GIFs. The images
+// are found in its traits as export_build_file does.
+//
+fn is_generated_piece(command: &Vec<u8>, images: &BTreeSet<String>) -> bool {
+	if added_piece_id(command).is_none() {
+		return false;
+	}
+
+	return String::from_utf8_lossy(command).replace('\\', "").split([ ';', ',', '\t' ]).any(|token| images.contains(token.trim()));
+}
+//
+// Stacks are added as "+/<id>/stack/<map>;<x>;<y>;<piece id>;...@@<layer>".
+//
+fn is_stack(command: &Vec<u8>) -> bool {
+	return added_piece_id(command).is_some_and(|id| command.starts_with(format!("{ADD_PIECE}/{id}/stack/").as_bytes()));
+}
+//
+// The stack without the pieces that were removed, None when it's left empty.
+//
+fn stack_without(command: &Vec<u8>, removed: &BTreeSet<String>) -> Option<Vec<u8>> {
+	let text: String = String::from_utf8_lossy(command).to_string();
+	let id: String = added_piece_id(command)?;
+	let (members, layer): (&str, &str) = text.strip_prefix(&format!("{ADD_PIECE}/{id}/stack/"))?.split_once("@@")?;
+	let fields: Vec<&str> = members.split(';').collect();
+	let pieces: Vec<&str> = fields.iter().skip(3).filter(|piece| !piece.is_empty() && !removed.contains(**piece)).copied().collect();
+
+	if 3 > fields.len() || pieces.is_empty() {
+		return None;
+	}
+
+	return Some(format!("{ADD_PIECE}/{id}/stack/{0};{1};@@{layer}", fields[..3].join(";"), pieces.join(";")).into_bytes());
+}
+//
+// The template's pieces of generated counters (and the stacks they leave empty) are replaced by the new ones, its other pieces
+// (notes, markers, boards' overlays ...) are kept.
+//
+fn write_save(path: &String, template: &Template, pieces: &Vec<String>, images: &BTreeSet<String>) -> Result<(), Box<dyn Error>> {
+	let removed: BTreeSet<String> = template.commands.iter().filter(|command| is_generated_piece(command, images)).filter_map(added_piece_id).collect();
+	let mut commands: Vec<Vec<u8>> = Default::default();
+	let mut added: bool = false;
+
+	for command in &template.commands {
+		if is_generated_piece(command, images) {
+			if !added {
+				commands.extend(pieces.iter().map(|piece| piece.as_bytes().to_vec()));
+				added = true;
+			}
+		} else if is_stack(command) {
+			commands.extend(stack_without(command, &removed));
+		} else {
+			if !added && command.starts_with(b"INFO\t") {
+				commands.extend(pieces.iter().map(|piece| piece.as_bytes().to_vec()));
+				added = true;
+			}
+
+			commands.push(command.to_vec());
+		}
+	}
+
+	if !added {
+		return Err(format!("{path}: nowhere to put the pieces in the template").into());
+	}
+
+	let mut zip = ZipWriter::new(File::create(path).map_err(|why| format!("couldn't create '{path}': {why}"))?);
+	let options: SimpleFileOptions = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+	zip.start_file(SAVED_GAME_ENTRY, options)?;
+	zip.write_all(obfuscate(&commands.join(&COMMAND_SEPARATOR)).as_bytes())?;
+
+	for (name, data) in &template.entries {
+		zip.start_file(name, options)?;
+		zip.write_all(data)?;
+	}
+
+	zip.finish()?;
+
+	return Ok(());
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = SavesArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let directory: String = if args.saves.is_empty() { REVIEW_SAVES_DIRECTORY.to_string() } else { format!("{0}/", args.saves.trim_end_matches('/')) };
+	let saves: BTreeMap<(String, String), Vec<&ManifestEntry>> = group_manifest_entries(&entries, &args.outputs, &args.selection)?;
+
+	let images: BTreeSet<String> = entries.iter().flat_map(|entry| [ entry.output.to_string(), legacy_image(&entry.output) ]).collect();
+
+	fs::create_dir_all(&directory)?;
+
+	for ((nationality, category), entries) in &saves {
+		let path: String = format!("{directory}{nationality}_{category}.vsav");
+		let template_path: String = if Path::new(&path).is_file() { path.to_string() } else { format!("{REVIEW_SAVES_DIRECTORY}{REVIEW_SAVE_TEMPLATE}") };
+		let template: Template = read_template(&template_path)?;
+		let pieces: Vec<String> = piece_commands(&args, &pair_fronts_and_backs(entries), &path);
+
+		if !args.outputs.quiet {
+			println!("Writing {path} ({0} pieces)", pieces.len());
+		}
+
+		write_save(&path, &template, &pieces, &images)?;
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn obfuscate_writes_the_key_and_the_xored_bytes() {
+		assert_eq!(obfuscate(&b"AB".to_vec()), format!("{OBFUSCATION_HEADER}c28380"));
+		assert_eq!(obfuscate(&Default::default()), format!("{OBFUSCATION_HEADER}c2"));
+	}
+
+	#[test]
+	fn deobfuscate_reverses_obfuscate() {
+		let data: Vec<u8> = "begin_save\u{1b}+/1/piece;;;ge/veh/x.svg;x\u{1b}end_save".as_bytes().to_vec();
+
+		assert_eq!(deobfuscate(&obfuscate(&data)), data);
+	}
+
+	#[test]
+	fn deobfuscate_uses_the_save_s_key() {
+		assert_eq!(deobfuscate(&format!("{OBFUSCATION_HEADER}0f4e4d")), b"AB".to_vec());
+		assert_eq!(deobfuscate(&OBFUSCATION_HEADER.to_string()), Vec::<u8>::new());
+	}
+
+	#[test]
+	fn deobfuscate_keeps_plain_saves() {
+		assert_eq!(deobfuscate(&"begin_save".to_string()), b"begin_save".to_vec());
+	}
+
+	#[test]
+	fn stack_without_drops_the_removed_pieces() {
+		let stack: Vec<u8> = b"+/9/stack/Main Map;100;200;1;2;3;@@2".to_vec();
+
+		assert_eq!(stack_without(&stack, &BTreeSet::from([ "2".to_string() ])), Some(b"+/9/stack/Main Map;100;200;1;3;@@2".to_vec()));
+		assert_eq!(stack_without(&stack, &BTreeSet::from([ "1".to_string(), "2".to_string(), "3".to_string() ])), None);
+	}
+}
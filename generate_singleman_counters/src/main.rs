@@ -1,5 +1,4 @@
 use std::io::prelude::*;
-use std::path::Path;
 use std::{error::Error, io, process};
 // This lets us write `#[derive(Deserialize)]`.
 use serde::Deserialize;
@@ -15,7 +14,15 @@ use common_functions::arguments::*;
 use common_functions::colors::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
 use common_functions::text_field::*;
 use common_functions::utils::*;
 
@@ -35,6 +42,19 @@ pub const COL_X_POSITION_SNIPER: f64 =	10.0;
 pub const COL_Y_POSITION: f64 =		 	 2.0;
 pub const COL_HEIGHT: f64 =				12.0;
 pub const COL_WIDTH: f64 =			 	 8.0;
+
+pub const SMC_MARKER_X_POSITION: f64 =	 3.0;
+pub const SMC_MARKER_Y_POSITION: f64 =	13.0;
+pub const SMC_MARKER_FONT_SIZE: f64 =	11.0;
+//
+// SMC kinds, prefixed to the values in the "values" column (e.g., "hero:1-4-9").
+//
+pub const SMC_KIND_DELIMITER: char =			':';
+pub const SMC_KIND_HERO: &str =					"hero";
+pub const SMC_KIND_WOUNDED_HERO: &str =			"wounded_hero";
+pub const SMC_KIND_COMMISSAR: &str =			"commissar";
+pub const SMC_KIND_BROKEN_COMMISSAR: &str =		"broken_commissar";
+pub const SMC_KIND_POLITICAL_OFFICER: &str =		"political_officer";	// Communist Chinese, the leadership is printed in red.
 //
 // Sanitized and parsed aircraft-specific record fields.
 //
@@ -42,6 +62,7 @@ pub const COL_WIDTH: f64 =			 	 8.0;
 struct Record {
 	args: Arguments,
 	nationality: String,
+	kind: String,
 	values: String,
 	armor_leader: bool,
 	assault_engineer: bool,
@@ -59,22 +80,14 @@ struct Record {
 impl Record {
 	fn generate_unit_depiction_svg_elements(&mut self, mut output: &std::fs::File, root_path: &String, size: u32) {
 		if INCLUDE_IMAGES {
-			let path_prefix = "svg/";
-			let file_type_svg = ".svg";
-			let file_type_png = ".png";
-	
-			let paths: Vec<std::string::String> = [
-				format!("{}{}{}", path_prefix, &self.piece, file_type_svg),
-				format!("{}{}{}", path_prefix, &self.piece, file_type_png)
-			].to_vec();
-	
-			for mut path in paths {
-				let mut pathname: String = root_path.to_string();
-				pathname.push_str(&path.to_string());
-	
-				if Path::new(&pathname).exists() {
+			let depiction_name: String = self.overrides.depiction(&self.piece);
+
+			match resolve_depiction(root_path, &depiction_name, &self.args) {
+				Some(depiction) => {
+					let href: &String = &depiction.href;
+					let depiction_source: String = depiction.source_attribute();
 					let mut transform: String = "scale(1.00)".to_string();
-	
+
 					if !self.svg_image_transform.is_empty() {
 						if self.svg_image_transform.contains("scale") {
 							transform = self.svg_image_transform.to_string();
@@ -82,13 +95,11 @@ impl Record {
 							transform = format!("{transform} {0}", self.svg_image_transform);
 						}
 					}
-	
-					if path.contains(SPACE) {
-						path = path.replace(SPACE, "%20");
-					}
-	
-					write!(output, "\t<image id=\"Silhouettes\" x=\"0\" y=\"0\" width=\"{size}\" height=\"{size}\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" style=\"opacity:{0:.2}\" href=\"{path}\" xlink:href=\"{path}\"/>\n", self.overrides.opacity).unwrap();
-					break;	// Our work here is done.
+
+					write!(output, "\t<image id=\"Silhouettes\" x=\"0\" y=\"0\" width=\"{size}\" height=\"{size}\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" style=\"opacity:{0:.2}\" href=\"{href}\" xlink:href=\"{href}\"{depiction_source}/>\n", self.overrides.opacity).unwrap();
+				}
+				None => {
+					missing_depiction(output, root_path, &depiction_name, size as f64 / 4.0, size as f64 / 4.0, size as f64 / 2.0, size as f64 / 2.0, &self.args);
 				}
 			}
 		}
@@ -164,9 +175,11 @@ impl Record {
 	}
 
 	fn generate_sniper_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		write!(output, "\t<text x=\"30\" y=\"19\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:red;fill-opacity:1;{FONT_MAIN}\">Pin: 3 DR</text>\n").unwrap();
-		write!(output, "\t<text x=\"30\" y=\"34\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:red;fill-opacity:1;{FONT_MAIN}\">K: &#8804;2 DR</text>\n").unwrap();
-		write!(output, "\t<text x=\"30\" y=\"49\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:red;fill-opacity:1;{FONT_MAIN}\">No Attack</text>\n").unwrap();		
+		let red: String = palette_color(RED);
+
+		write!(output, "\t<text x=\"30\" y=\"19\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:{red};fill-opacity:1;{FONT_MAIN}\">Pin: 3 DR</text>\n").unwrap();
+		write!(output, "\t<text x=\"30\" y=\"34\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:{red};fill-opacity:1;{FONT_MAIN}\">K: &#8804;2 DR</text>\n").unwrap();
+		write!(output, "\t<text x=\"30\" y=\"49\" style=\"font-size:13px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:semi-expanded;text-anchor:middle;fill:{red};fill-opacity:1;{FONT_MAIN}\">No Attack</text>\n").unwrap();		
 	}
 	
 	fn generate_broken_leader_counter_svg_elements(&mut self, mut output: &std::fs::File) {
@@ -187,6 +200,55 @@ impl Record {
 		write!(output, "\t</svg>\n").unwrap();
 	}
 	
+	//
+	// Broken and wounded leaders, broken commissars, wounded heroes and the "_xxx" rows (armor leader, sniper) are the back of
+	// another row's counter.
+	//
+	fn side(&self) -> &'static str {
+		if self.broken || self.wounded || self.values.starts_with('_') || SMC_KIND_WOUNDED_HERO == self.kind || SMC_KIND_BROKEN_COMMISSAR == self.kind {
+			return MANIFEST_BACK;
+		}
+		
+		return MANIFEST_FRONT;
+	}
+
+	//
+	// The backs a leader can have besides its own "<piece>b": armor leaders share their nationality's "_aleader" one ("gealb").
+	//
+	fn back_pieces(&self) -> Vec<String> {
+		let mut result: Vec<String> = Default::default();
+
+		if self.armor_leader {
+			result.push(format!("{0}alb", self.nationality));
+		}
+
+		return result;
+	}
+
+	fn generate_smc_marker_svg_elements(&mut self, mut output: &std::fs::File, marker: &str, color: &String) {
+		let x_position = if "ff" == self.nationality { SMC_MARKER_X_POSITION + COL_WIDTH } else { SMC_MARKER_X_POSITION };
+		
+		write!(output, "\t<text id=\"Marker\" x=\"{x_position}\" y=\"{SMC_MARKER_Y_POSITION}\" style=\"font-size:{SMC_MARKER_FONT_SIZE}px;font-style:normal;font-variant:normal;{FONT_WEIGHT_BOLD};text-anchor:start;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{marker}</text>\n").unwrap();
+	}
+
+	fn generate_hero_counter_svg_elements(&mut self, output: &std::fs::File) {
+		let color = self.colors.text.to_string();
+		
+		self.wounded = SMC_KIND_WOUNDED_HERO == self.kind;
+		self.generate_leader_counter_svg_elements(output);
+		self.generate_smc_marker_svg_elements(output, "H", &color);
+	}
+
+	fn generate_commissar_counter_svg_elements(&mut self, output: &std::fs::File) {
+		if SMC_KIND_BROKEN_COMMISSAR == self.kind {
+			self.generate_broken_leader_counter_svg_elements(output);
+		} else {
+			self.generate_leader_counter_svg_elements(output);
+		}
+		
+		self.generate_smc_marker_svg_elements(output, &STAR.to_string(), &palette_color(RED));
+	}
+	
 	fn generate_leader_counter_svg_elements(&mut self, mut output: &std::fs::File) {
 		let mut font_size = 12.0;
 		let mut y_position: f64 = 24.0;
@@ -211,8 +273,8 @@ impl Record {
 			write!(output, "\t</svg>\n").unwrap();
 		}
 
-		if self.piece.contains("PO") { // Special case for Communist Chinese Political Officers.
-			write!(output, "\t<text id=\"Values\" transform=\"translate(44,{y_position}) rotate(-90)\" style=\"font-size:{font_size}px;font-style:normal;font-variant:normal;{FONT_WEIGHT_BOLD};font-stretch:semi-expanded;text-anchor:middle;fill:red;fill-opacity:1;stroke:black;stroke-width:0.5;stroke-opacity:1;{FONT_MAIN}\">{0}</text>\n", self.values).unwrap();
+		if SMC_KIND_POLITICAL_OFFICER == self.kind {
+			write!(output, "\t<text id=\"Values\" transform=\"translate(44,{y_position}) rotate(-90)\" style=\"font-size:{font_size}px;font-style:normal;font-variant:normal;{FONT_WEIGHT_BOLD};font-stretch:semi-expanded;text-anchor:middle;fill:{0};fill-opacity:1;stroke:black;stroke-width:0.5;stroke-opacity:1;{FONT_MAIN}\">{1}</text>\n", palette_color(RED), self.values).unwrap();
 		} else {
 			write!(output, "\t<text id=\"Values\" transform=\"translate(44,{y_position}) rotate(-90)\" style=\"font-size:{font_size}px;font-style:normal;font-variant:normal;{FONT_WEIGHT_BOLD};font-stretch:semi-expanded;text-anchor:middle;fill:{0};fill-opacity:1;{FONT_MAIN}\">{1}</text>\n", self.colors.text, self.values).unwrap();
 		}
@@ -223,11 +285,11 @@ impl Record {
 	}
 }
 
-fn generate_svg_counter(record: &mut Record) {
-	let path = format!("{0}{1}/", record.args.destination, record.nationality);
+fn generate_svg_counter(record: &mut Record) -> io::Result<()> {
+	let path = construct_path(&record.nationality, "inf", &record.args.destination);
 	
 	if record.overrides.copy {
-		let _ = copy_counter("", &record.nationality, &record.piece, &"".to_string(), &record.args);
+		copy_counter("", &record.nationality, &record.piece, &"".to_string(), record.side(), &record.args)?;
 	} else {
 		let size: u32 = if 0 != record.overrides.counter_size { record.overrides.counter_size } else { 48 };
 		
@@ -244,7 +306,10 @@ fn generate_svg_counter(record: &mut Record) {
 			Ok(output) => output,
 		};
 
-		generate_counter_header_svg_elements("vasl_singleman_counters", &output, size, &record.piece, &"".to_string(), &record.comments, &record.version);
+		add_manifest_entry(&path, &record.piece, &record.nationality, record.side(), MANIFEST_GENERATED);
+		add_manifest_backs(&path, &record.piece, &record.back_pieces());
+
+		generate_counter_header_svg_elements("vasl_singleman_counters", &output, size, &record.piece, &"".to_string(), &record.comments, &record.version, &CounterMetadata::new("singleman", &record.nationality).for_piece(&record.piece));
 		generate_counter_background_svg(&output, size, &record.colors, &record.overrides);
 		generate_debug_working_area_svg(&output);
 
@@ -270,7 +335,11 @@ fn generate_svg_counter(record: &mut Record) {
 				record.generate_sniper_back_svg_elements(&output);
 			}
 			&_ => {
-				if record.armor_leader {
+				if SMC_KIND_HERO == record.kind || SMC_KIND_WOUNDED_HERO == record.kind {
+					record.generate_hero_counter_svg_elements(&output);
+				} else if SMC_KIND_COMMISSAR == record.kind || SMC_KIND_BROKEN_COMMISSAR == record.kind {
+					record.generate_commissar_counter_svg_elements(&output);
+				} else if record.armor_leader {
 					record.generate_armor_leader_counter_svg_elements(&output);
 				} else if record.broken {
 					record.generate_broken_leader_counter_svg_elements(&output);
@@ -288,6 +357,8 @@ fn generate_svg_counter(record: &mut Record) {
 			println!(" done.");
 		}
 	}
+
+	Ok(())
 }
 //
 // We don't need to derive `Debug` (which doesn't require Serde), but it's a
@@ -323,7 +394,19 @@ impl SpreadsheetRecord {
 		
 		result.overrides.sanitize(&self.overrides);
 		
-		result.values = self.values.to_string();
+		if self.values.contains(SMC_KIND_DELIMITER) {
+			let (kind, values) = self.values.split_once(SMC_KIND_DELIMITER).unwrap();
+			
+			match kind {
+				SMC_KIND_HERO | SMC_KIND_WOUNDED_HERO | SMC_KIND_COMMISSAR | SMC_KIND_BROKEN_COMMISSAR | SMC_KIND_POLITICAL_OFFICER => {}
+				&_ => panic!("SpreadsheetRecord.sanitize()@{0}: unimplemented! SMC kind '{1}'", line!(), kind)
+			}
+			
+			result.kind = kind.to_string();
+			result.values = values.to_string();
+		} else {
+			result.values = self.values.to_string();
+		}
 		
 		result.armor_leader = "yes" == self.armor;
 		
@@ -351,32 +434,45 @@ fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
 	
+	let manifest: ManifestWriter = ManifestWriter::new("inf", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
+
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+		set_manifest_source_row(&row);
 
 		if !spreadsheet_record.nationality.is_empty() && !spreadsheet_record.overrides.contains(NOVR_IGNORE) {
 			let mut record: Record = spreadsheet_record.sanitize(&"".to_string(), &args);
 			let pieces = record.pieces.clone();
 			
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.piece = piece.to_string();
-					generate_svg_counter(&mut record);
+					generate_svg_counter(&mut record)?;
 				} else {
-					let (piece, nationality) = piece.split_once("@").unwrap();
+					let (piece, nationality) = split_alternate_nationality(&piece);
 					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					
 					alt_record.colors = nationality_to_colors(&nationality.to_string());
 					alt_record.piece = piece.to_string();
 					
-					generate_svg_counter(&mut alt_record);
+					generate_svg_counter(&mut alt_record)?;
 				}
 			}			
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
@@ -1,6 +1,8 @@
 use std::io::prelude::*;
-use std::path::Path;
 use std::{error::Error, io, process};
+use std::fs::File;
+use std::sync::OnceLock;
+use regex::Regex;
 // This lets us write `#[derive(Deserialize)]`.
 use serde::Deserialize;
 //
@@ -15,7 +17,16 @@ use common_functions::arguments::*;
 use common_functions::colors::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
+use common_functions::inline::*;
+use common_functions::malfunction::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
 use common_functions::text_field::*;
 use common_functions::utils::*;
 
@@ -48,6 +59,33 @@ pub const COL_WIDTH: f64 =			 8.0;
 pub const ROF_SIZE: f64 =			12.0;
 pub const ROF_STROKE_WIDTH: f64 =	 0.75;
 //
+// Data-driven back side tables (range, TH#, TK#).
+//
+pub const BACK_TEMPLATES_FILE: &str =	"./data/sw_backs.csv";
+
+pub const BACK_TABLE_RANGE_X_POSITION: f64 =		17.0;
+pub const BACK_TABLE_TO_HIT_X_POSITION: f64 =		38.0;
+pub const BACK_TABLE_TO_HIT_TK_X_POSITION: f64 =	31.0;
+pub const BACK_TABLE_TK_X_POSITION: f64 =			43.0;
+pub const BACK_TABLE_RANGE_LABEL_X_POSITION: f64 =	11.0;
+pub const BACK_TABLE_TO_HIT_LABEL_X_POSITION: f64 =	32.0;
+pub const BACK_TABLE_TO_HIT_TK_LABEL_X_POSITION: f64 =	25.0;
+pub const BACK_TABLE_LABEL_Y_POSITION: f64 =		24.0;
+pub const BACK_TABLE_LABEL_REPAIR_Y_POSITION: f64 =	28.0;
+pub const BACK_TABLE_FIRST_ROW_Y_POSITION: f64 =	12.0;
+pub const BACK_TABLE_ROW_HEIGHT: f64 =				 7.0;
+pub const BACK_TABLE_FONT_SIZE: f64 =				 8.0;
+pub const BACK_TABLE_LABEL_FONT_SIZE: f64 =			 9.0;
+pub const BACK_TABLE_TK_FONT_SIZE: f64 =			 7.0;
+pub const BACK_TABLE_NOTE_FONT_SIZE: f64 =			 5.0;
+//
+// The year a back side table's "years" are compared to ends the piece name, after the weapon's letters or in parentheses (e.g.,
+// "amBAZ43b", "brBAZ44ab" or "usBaz(50)KFW-back"), so that "MMG-42a" or "50mm" don't give one.
+//
+pub const BACK_TABLE_PIECE_YEAR: &str =	r"(?:[A-Za-z]|\()(?<year>[0-9]{2})(?:\)|[a-z]?b?$)";
+
+static PIECE_YEAR: OnceLock<Regex> = OnceLock::new();
+//
 // Sanitized and parsed aircraft-specific record fields.
 //
 #[derive(Default)]
@@ -124,22 +162,7 @@ impl Record {
 	}
 	
 	fn sanitize_repair(&mut self, repair: &str) {
-		if !repair.is_empty() {
-			if repair.contains("/") {
-				let (left, right) = repair.split_once('/').unwrap();
-			
-				self.repair = left.parse::<usize>().unwrap_or(0);
-				self.disable = right.parse::<usize>().unwrap_or(0);
-				
-				if 0 == self.repair && 0 == self.disable {
-					self.repair_text = left.to_string();
-					self.disable_text = right.to_string();
-				}
-			} else {
-				self.repair = repair.parse::<usize>().unwrap_or(0);
-				self.disable = self.repair;
-			}
-		}
+		(self.repair, self.disable, self.repair_text, self.disable_text) = split_sw_repair(repair);
 	}
 	
 	fn sanitize_leadership(&mut self, leadership: &str) {
@@ -158,22 +181,14 @@ impl Record {
 	
 	fn generate_unit_depiction_svg_elements(&mut self, mut output: &std::fs::File, root_path: &String) {
 		if INCLUDE_IMAGES {
-			let path_prefix = "svg/";
-			let file_type_svg = ".svg";
-			let file_type_png = ".png";
-	
-			let paths: Vec<std::string::String> = [
-				format!("{}{}{}", path_prefix, &self.piece, file_type_svg),
-				format!("{}{}{}", path_prefix, &self.piece, file_type_png)
-			].to_vec();
-	
-			for mut path in paths {
-				let mut pathname: String = root_path.to_string();
-				pathname.push_str(&path.to_string());
-	
-				if Path::new(&pathname).exists() {
+			let depiction_name: String = self.overrides.depiction(&self.piece);
+
+			match resolve_depiction(root_path, &depiction_name, &self.args) {
+				Some(depiction) => {
+					let href: &String = &depiction.href;
+					let depiction_source: String = depiction.source_attribute();
 					let mut transform: String = "scale(1.00)".to_string();
-	
+
 					if !self.svg_image_transform.is_empty() {
 						if self.svg_image_transform.contains("scale") {
 							transform = self.svg_image_transform.to_string();
@@ -181,14 +196,12 @@ impl Record {
 							transform = format!("{transform} {0}", self.svg_image_transform);
 						}
 					}
-	
-					if path.contains(SPACE) {
-						path = path.replace(SPACE, "%20");
-					}
-	
+
 					write!(output, "\t<!-- Unit Depiction -->\n").unwrap();
-					write!(output, "\t<image x=\"0\" y=\"0\" width=\"100%\" height=\"100%\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{path}\" xlink:href=\"{path}\"/>\n").unwrap();
-					break;	// Our work here is done.
+					write!(output, "\t<image x=\"0\" y=\"0\" width=\"100%\" height=\"100%\" preserveAspectRatio=\"xMidYMid meet\" transform=\"{transform}\" href=\"{href}\" xlink:href=\"{href}\"{depiction_source}/>\n").unwrap();
+				}
+				None => {
+					missing_depiction(output, root_path, &depiction_name, 9.0, 9.0, 30.0, 30.0, &self.args);
 				}
 			}
 		}
@@ -236,79 +249,6 @@ impl Record {
 		self.generate_rof_svg_elements(output);
 	}
 
-	fn generate_baz_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		let color = &self.colors.text;
-		//
-		// Common elements, "Range" and "TH#".
-		//
-		write!(output, "\t<text transform=\"translate(11,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Range</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(32,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
-		//
-		// '43 & '44 Bazookas all have 4 hex range, everything else has 5 hex range.
-		//
-		if self.piece.contains("43") || self.piece.contains("44") {
-			write!(output, "\t<text x=\"17\" y=\"12\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">0</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"19\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">1</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"26\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">2</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"33\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">3</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"40\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">4</text>\n").unwrap();			
-		} else {
-			write!(output, "\t<text x=\"17\" y=\"10\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">0</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"17\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">1</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"24\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">2</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"31\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">3</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"38\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">4</text>\n").unwrap();
-			write!(output, "\t<text x=\"17\" y=\"45\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">5</text>\n").unwrap();			
-		}
-		//
-		// Now the variations on the TH#s gets kinda wonky.
-		//
-		if self.piece.contains("43") || self.piece.contains("44") {
-			let mut th_0 = 11;
-			let mut th_1 =  9;
-			let mut th_2 =  8;
-			let mut th_3 =  7;
-			let mut th_4 =  4;
-			
-			if self.piece.contains("cc") {
-				th_0 = 10;
-				th_1 =  8;
-				th_2 =  7;
-				th_3 =  6;
-				th_4 =  3;
-			}
-
-			write!(output, "\t<text x=\"38\" y=\"12\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_0}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"19\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_1}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"26\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_2}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"33\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_3}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"40\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_4}</text>\n").unwrap();				
-		} else {
-			let mut th_0 = 11;
-			let mut th_1 = 10;
-			let mut th_2 =  9;
-			let mut th_3 =  8;
-			let mut th_4 =  6;
-			let mut th_5 =  4;
-			
-			if self.piece.contains("cc") && self.piece.contains("50") || self.piece.contains("51") {
-				th_0 = 10;
-				th_1 =  9;
-				th_2 =  8;
-				th_3 =  7;
-				th_4 =  5;
-				th_5 =  3;
-			}
-			
-			write!(output, "\t<text x=\"38\" y=\"10\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_0}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"17\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_1}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"24\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_2}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"31\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_3}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"38\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_4}</text>\n").unwrap();
-			write!(output, "\t<text x=\"38\" y=\"45\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{th_5}</text>\n").unwrap();			
-		}
-	}	
-
 	fn generate_dc_back_svg_elements(&mut self, mut output: &std::fs::File) {
 		let color = &self.colors.text;
 		
@@ -331,32 +271,6 @@ impl Record {
 		write!(output, "\t<text x=\"50%\" y=\"42.00\" style=\"font-size:8.5px;font-style:normal;font-variant:normal;font-weight:normal;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">vs Owner</text>\n").unwrap();			
 	}
 
-	fn generate_pf_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		let color = &self.colors.text;
-		
-		write!(output, "\t<text transform=\"translate(11,24) rotate(-90)\" style=\"font-size:9.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\"\">Range</text>\n").unwrap();
-		write!(output, "\t<text x=\"17.00\" y=\"12.00\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}L\">0</text>\n").unwrap();
-		write!(output, "\t<text x=\"17.00\" y=\"22.50\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}L\">1</text>\n").unwrap();
-		write!(output, "\t<text x=\"17.00\" y=\"32.50\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}L\">2</text>\n").unwrap();
-		write!(output, "\t<text x=\"17.00\" y=\"42.00\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}L\">3</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(32,24) rotate(-90)\" style=\"font-size:9.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
-		write!(output, "\t<text x=\"38.00\" y=\"12.00\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">10</text>\n").unwrap();
-		write!(output, "\t<text x=\"38.00\" y=\"22.50\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">8</text>\n").unwrap();
-		write!(output, "\t<text x=\"38.00\" y=\"32.50\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">6</text>\n").unwrap();
-		write!(output, "\t<text x=\"38.00\" y=\"42.00\" style=\"font-size:8.00px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">4</text>\n").unwrap();
-	}
-
-	fn generate_pfk_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		let color = &self.colors.text;
-
-		write!(output, "\t<text transform=\"translate(11,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Range</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"22.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">0</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"32.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">1</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(32,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"22.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">10</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"32.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">8</text>\n").unwrap();		
-	}
-	
 	fn generate_phone_back_svg_elements(&mut self, mut output: &std::fs::File) {
 		let color = &self.colors.text;
 		
@@ -367,41 +281,47 @@ impl Record {
 		write!(output, "\t<text x=\"50%\" y=\"44.00\" style=\"font-size:8.5px;font-style:normal;font-variant:normal;font-weight:normal;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Removes</text>\n").unwrap();	
 	}
 
-	fn generate_piat_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		let color = &self.colors.text;
+	fn generate_back_table_svg_elements(&mut self, mut output: &std::fs::File, rows: &Vec<&BackTemplateRecord>) {
+		let color = self.colors.text.to_string();
 		let x_color = &self.colors.malfunction_x;
-
-		write!(output, "\t<line x1=\"5\" y1=\"5\" x2=\"43\" y2=\"43\" style=\"stroke:{x_color}; stroke-width:3.00\"/>\n").unwrap();
-		write!(output, "\t<line x1=\"5\" y1=\"43\" x2=\"43\" y2=\"5\" style=\"stroke:{x_color}; stroke-width:3.00\"/>\n").unwrap();
-		write!(output, "\t<text x=\"3\" y=\"10\" style=\"font-size:10px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:start;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">R2</text>\n").unwrap();
-		write!(output, "\t<text x=\"45\" y=\"10\" style=\"font-size:10px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:end;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">X6</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(11,28) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Range</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"19.0\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">0</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"26.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">1</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"34.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">2</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"42.0\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">3</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(32,28) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"19.0\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">11</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"26.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">9</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"34.5\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">7</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"42.0\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">5</text>\n").unwrap();		
-	}
-
-	fn generate_psk_back_svg_elements(&mut self, mut output: &std::fs::File) {
-		let color = &self.colors.text;
-
-		write!(output, "\t<text transform=\"translate(11,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Range</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"12\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">0</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"19\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">1</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"26\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">2</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"33\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">3</text>\n").unwrap();
-		write!(output, "\t<text x=\"17\" y=\"40\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">4</text>\n").unwrap();
-		write!(output, "\t<text transform=\"translate(32,24) rotate(-90)\" style=\"font-size:9px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"12\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">11</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"19\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">9</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"26\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">8</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"33\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">7</text>\n").unwrap();
-		write!(output, "\t<text x=\"38\" y=\"40\" style=\"font-size:8px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">4</text>\n").unwrap();		
+		let has_tk = rows.iter().any(|row| !row.tk.is_empty());
+		let repair = rows.iter().map(|row| row.repair.to_string()).find(|repair| !repair.is_empty()).unwrap_or_default();
+		let label_y = if repair.is_empty() { BACK_TABLE_LABEL_Y_POSITION } else { BACK_TABLE_LABEL_REPAIR_Y_POSITION };
+		let to_hit_x = if has_tk { BACK_TABLE_TO_HIT_TK_X_POSITION } else { BACK_TABLE_TO_HIT_X_POSITION };
+		let to_hit_label_x = if has_tk { BACK_TABLE_TO_HIT_TK_LABEL_X_POSITION } else { BACK_TABLE_TO_HIT_LABEL_X_POSITION };
+		
+		if !repair.is_empty() {
+			let (r, x) = repair.split_once('/').unwrap_or((&repair, ""));
+			
+			write!(output, "\t<line x1=\"5\" y1=\"5\" x2=\"43\" y2=\"43\" style=\"stroke:{x_color}; stroke-width:3.00\"/>\n").unwrap();
+			write!(output, "\t<line x1=\"5\" y1=\"43\" x2=\"43\" y2=\"5\" style=\"stroke:{x_color}; stroke-width:3.00\"/>\n").unwrap();
+			write!(output, "\t<text x=\"3\" y=\"10\" style=\"font-size:10px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:start;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">R{r}</text>\n").unwrap();
+			
+			if !x.is_empty() {
+				write!(output, "\t<text x=\"45\" y=\"10\" style=\"font-size:10px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:end;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">X{x}</text>\n").unwrap();
+			}
+		}
+		
+		write!(output, "\t<text transform=\"translate({BACK_TABLE_RANGE_LABEL_X_POSITION},{label_y}) rotate(-90)\" style=\"font-size:{BACK_TABLE_LABEL_FONT_SIZE}px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">Range</text>\n").unwrap();
+		write!(output, "\t<text transform=\"translate({to_hit_label_x},{label_y}) rotate(-90)\" style=\"font-size:{BACK_TABLE_LABEL_FONT_SIZE}px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">TH#</text>\n").unwrap();
+		
+		for (idx, row) in rows.iter().enumerate() {
+			let y = row.y.parse::<f64>().unwrap_or(BACK_TABLE_FIRST_ROW_Y_POSITION + (idx as f64 * BACK_TABLE_ROW_HEIGHT));
+			let mut to_hit = row.to_hit.to_string();
+			
+			if !row.note.is_empty() {
+				let note = convert_text(&row.note, FIVE_LOBED_ASTERISK_TAG, FIVE_LOBED_ASTERISK_UC);
+				
+				to_hit.push_str(&wrap_superscripts(&convert_text(&note, SIX_LOBED_ASTERISK_TAG, SIX_LOBED_ASTERISK_UC), BACK_TABLE_NOTE_FONT_SIZE));
+			}
+			
+			write!(output, "\t<text x=\"{BACK_TABLE_RANGE_X_POSITION}\" y=\"{y}\" style=\"font-size:{BACK_TABLE_FONT_SIZE}px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{0}</text>\n", row.range).unwrap();
+			write!(output, "\t<text x=\"{to_hit_x}\" y=\"{y}\" style=\"font-size:{BACK_TABLE_FONT_SIZE}px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{to_hit}</text>\n").unwrap();
+			
+			if !row.tk.is_empty() {
+				write!(output, "\t<text x=\"{BACK_TABLE_TK_X_POSITION}\" y=\"{y}\" style=\"font-size:{BACK_TABLE_TK_FONT_SIZE}px;font-style:normal;font-variant:normal;font-weight:bold;font-stretch:normal;text-anchor:middle;fill:{color};fill-opacity:1;font-family:{FONT_MAIN}\">{0}</text>\n", row.tk).unwrap();
+			}
+		}
 	}
 	
 	fn generate_counter_malfunction_x_svg_elements(&mut self, mut output: &std::fs::File) {
@@ -429,6 +349,23 @@ impl Record {
 		}		
 	}
 	
+	//
+	// Malfunctioned ("Malf" and the dismantled ones, breakdown "X") and the other "_xxx" rows are the back of another row's counter.
+	//
+	fn side(&self) -> &'static str {
+		if "Malf" == self.counter || "X" == self.breakdown || self.counter.starts_with('_') {
+			return MANIFEST_BACK;
+		}
+		
+		return MANIFEST_FRONT;
+	}
+	//
+	// Machine guns, light mortars and ATRs get their malfunctioned back side from the front's "repair" column.
+	//
+	fn malfunctioned_back_token(&self) -> &'static str {
+		return sw_repair_back_token(&self.counter, &self.value, self.repair, &self.repair_text);
+	}
+	
 	fn generate_strength_svg_elements(&mut self, mut output: &std::fs::File) {
 		if !self.value.is_empty() {
 			let mut values = self.value.to_string();
@@ -605,8 +542,8 @@ impl Record {
 	}
 }
 
-fn generate_svg_counter(record: &mut Record) {
-	let path = format!("{0}{1}/", &record.args.destination.to_string(), &record.nationality);
+fn generate_svg_counter(record: &mut Record, back_templates: &Vec<BackTemplateRecord>, side: &str) {
+	let path = construct_path(&record.nationality, "sw", &record.args.destination);
 	let size: u32 = if 0 != record.overrides.counter_size { record.overrides.counter_size } else { 48 };
 	//
 	// Create the counter file.
@@ -616,7 +553,9 @@ fn generate_svg_counter(record: &mut Record) {
 		Ok(output) => output,
 	};
 
-	generate_counter_header_svg_elements("vasl_sw_counters", &output, size, &record.piece, &"".to_string(), &record.comments, &record.version);
+	add_manifest_entry(&path, &record.piece, &record.nationality, side, MANIFEST_GENERATED);
+
+	generate_counter_header_svg_elements("vasl_sw_counters", &output, size, &record.piece, &"".to_string(), &record.comments, &record.version, &CounterMetadata::new("support_weapon", &record.nationality).for_piece(&record.piece));
 	generate_counter_background_svg(&output, size, &record.colors, &record.overrides);
 	generate_debug_working_area_svg(&output);
 
@@ -632,7 +571,7 @@ fn generate_svg_counter(record: &mut Record) {
 		}
 		&_ => {
 			if record.counter.starts_with('_') {
-				generate_counter_back_svg_elements(&output, record);
+				generate_counter_back_svg_elements(&output, record, &path, back_templates);
 			} else {
 				generate_sw_counter_svg_elements(&output, record, &path);
 			}
@@ -665,10 +604,17 @@ fn generate_sfcp_counter_svg_elements(output: &std::fs::File, record: &mut Recor
 	record.generate_sfcp_type_svg_elements(&output);
 }
 
-fn generate_counter_back_svg_elements(output: &std::fs::File, record: &mut Record) {
+fn generate_counter_back_svg_elements(output: &std::fs::File, record: &mut Record, path: &String, back_templates: &Vec<BackTemplateRecord>) {
+	let rows = select_back_template(back_templates, &record.counter, &record.nationality, &record.piece);
+	
+	if !rows.is_empty() {
+		record.generate_back_table_svg_elements(&output, &rows);
+		return;
+	}
+
 	match record.counter.as_str() {
-		"_baz" => {
-			record.generate_baz_back_svg_elements(&output);
+		BACK_TOKEN_ATR | BACK_TOKEN_MG | BACK_TOKEN_MTR => {
+			generate_sw_malf_counter_svg_elements(&output, record, &path);
 		}
 		"_dc" => {
 			record.generate_dc_back_svg_elements(&output);
@@ -676,31 +622,65 @@ fn generate_counter_back_svg_elements(output: &std::fs::File, record: &mut Recor
 		"_ft" => {
 			record.generate_ft_back_svg_elements(&output);
 		}
-		"_pf" => {
-			record.generate_pf_back_svg_elements(&output);
-		}
-		"_pfk" => {
-			record.generate_pfk_back_svg_elements(&output);
-		}		
 		"_phone" => {
 			record.generate_phone_back_svg_elements(&output);
 		}
-		"_piat" => {
-			record.generate_piat_back_svg_elements(&output);
-		}
-		"_psk" => {
-			record.generate_psk_back_svg_elements(&output);
-		}
 		&_ => {
 			panic!("Unknown counter back token '{0}'", record.counter);
 		}
 	}
 	
 }
+//
+// Selects the rows of the most specific back side table for the token, a table's nationality and years (e.g., "43-44",
+// compared to the year in the piece name) must match when present.
+//
+fn select_back_template<'a>(back_templates: &'a Vec<BackTemplateRecord>, token: &String, nationality: &String, piece: &String) -> Vec<&'a BackTemplateRecord> {
+	let re_year = PIECE_YEAR.get_or_init(|| Regex::new(BACK_TABLE_PIECE_YEAR).unwrap());
+	let year = match re_year.captures(piece) {
+		Some(caps) => caps["year"].parse::<u32>().unwrap_or(0),
+		None => 0,
+	};
+	let mut best_score = -1;
+	let mut best_key: (String, String) = Default::default();
+	
+	for template in back_templates {
+		if *token != template.token {
+			continue;
+		}
+		
+		let mut score = 0;
+		
+		if !template.nationality.is_empty() {
+			if *nationality != template.nationality {
+				continue;
+			}
+			
+			score += 2;
+		}
+		
+		if !template.years.is_empty() {
+			let (first, last) = template.years.split_once(MINUS).unwrap_or((&template.years, &template.years));
+			
+			if year < first.parse::<u32>().unwrap_or(0) || year > last.parse::<u32>().unwrap_or(0) {
+				continue;
+			}
+			
+			score += 1;
+		}
+		
+		if score > best_score {
+			best_score = score;
+			best_key = (template.nationality.to_string(), template.years.to_string());
+		}
+	}
+	
+	return back_templates.iter().filter(|template| *token == template.token && best_key.0 == template.nationality && best_key.1 == template.years).collect();
+}
 
-fn generate_svg_counter_announcer(record: &mut Record) {
+fn generate_svg_counter_announcer(record: &mut Record, back_templates: &Vec<BackTemplateRecord>) -> io::Result<()> {
 	if record.overrides.copy {
-		let _ = copy_counter("", &record.nationality, &record.piece, &"".to_string(), &record.args);
+		copy_counter("", &record.nationality, &record.piece, &"".to_string(), record.side(), &record.args)?;
 	} else if "Ignore" != record.counter {
 		if !record.args.quiet {
 			print!("Generating '{0}.svg' ...", record.piece);
@@ -708,12 +688,47 @@ fn generate_svg_counter_announcer(record: &mut Record) {
 			println!("{0}", record.piece);
 		}
 		
-		generate_svg_counter(record);
+		generate_svg_counter(record, back_templates, record.side());
 		
 		if !record.args.quiet {
 			println!(" done.");
 		}
+		
+		let back_token = record.malfunctioned_back_token();
+		
+		if !back_token.is_empty() && !record.counter.starts_with('_') {
+			let front_counter = record.counter.to_string();
+			let front_piece = record.piece.to_string();
+			let front_pp = record.pp;
+			let front_extra_info = record.overrides.extra_info.to_string();
+			//
+			// Like the "Malf" rows, the back has no PP number and its own extra information ("back_extra=").
+			//
+			record.counter = back_token.to_string();
+			record.piece = format!("{front_piece}{BACK_PIECE_SUFFIX}");
+			record.pp = 0;
+			record.overrides.extra_info = record.overrides.back_extra_info.to_string();
+			
+			if !record.args.quiet {
+				print!("Generating '{0}.svg' ...", record.piece);
+			} else {
+				println!("{0}", record.piece);
+			}
+			
+			generate_svg_counter(record, back_templates, MANIFEST_BACK);
+			
+			if !record.args.quiet {
+				println!(" done.");
+			}
+			
+			record.counter = front_counter;
+			record.piece = front_piece;
+			record.pp = front_pp;
+			record.overrides.extra_info = front_extra_info;
+		}
 	}
+
+	Ok(())
 }			
 //
 // We don't need to derive `Debug` (which doesn't require Serde), but it's a
@@ -789,36 +804,84 @@ impl SpreadsheetRecord {
 	}
 }
 
+//
+// One row of a back side table, rows of the same token, nationality and years make up one table.
+//
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+#[serde(rename_all = "lowercase")]
+struct BackTemplateRecord {
+	token: String,
+	nationality: String,
+	years: String,
+	range: String,
+	to_hit: String,
+	tk: String,
+	note: String,
+	y: String,
+	repair: String,
+	comments: String,
+}
+
+fn read_back_templates() -> Result<Vec<BackTemplateRecord>, Box<dyn Error>> {
+	let mut result: Vec<BackTemplateRecord> = Default::default();
+	let file = match File::open(BACK_TEMPLATES_FILE) {
+		Err(why) => panic!("couldn't open file: {0} {1}", BACK_TEMPLATES_FILE, why),
+		Ok(file) => file,
+	};
+	let mut rdr = csv::Reader::from_reader(file);
+	
+	for template in rdr.deserialize() {
+		result.push(template?);
+	}
+	
+	return Ok(result);
+}
+
 fn run() -> Result<(), Box<dyn Error>> {
     let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
 	
+	let back_templates = read_back_templates()?;
+	let manifest: ManifestWriter = ManifestWriter::new("sw", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
+
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+		set_manifest_source_row(&row);
 
 		if !spreadsheet_record.nationality.is_empty() {
 			let mut record: Record = spreadsheet_record.sanitize(&"".to_string(), &args);
 			let pieces = record.pieces.clone();
 			
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.piece = piece.to_string();
-					generate_svg_counter_announcer(&mut record);
+					generate_svg_counter_announcer(&mut record, &back_templates)?;
 				} else {
-					let (piece, nationality) = piece.split_once("@").unwrap();
+					let (piece, nationality) = split_alternate_nationality(&piece);
 					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					
 					alt_record.colors = nationality_to_colors(&nationality.to_string());
 					alt_record.piece = piece.to_string();
 					
-					generate_svg_counter_announcer(&mut alt_record);
+					generate_svg_counter_announcer(&mut alt_record, &back_templates)?;
 				}
 			}			
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
@@ -16,10 +16,20 @@ use common_functions::colors::*;
 use common_functions::common_record::*;
 use common_functions::debugging::*;
 use common_functions::defines::*;
+use common_functions::depictions::*;
 use common_functions::machine_guns::*;
 use common_functions::malfunction::*;
+use common_functions::markings::*;
+use common_functions::inline::*;
+use common_functions::manifest::*;
+use common_functions::metadata::*;
 use common_functions::movement::*;
+use common_functions::nationalities::*;
+use common_functions::optimize::*;
 use common_functions::overrides::*;
+use common_functions::palettes::*;
+use common_functions::profiles::*;
+use common_functions::smoke::*;
 use common_functions::text_field::*;
 use common_functions::transport::*;
 use common_functions::utils::*;
@@ -36,6 +46,8 @@ struct Record {
 	armor: ArmorValues,
 	movement_values: VehicleMovementValues,
 	transport_values: TransportValues,
+	smoke: SmokeValues,
+	markings: VehicleMarkings,
 }
 
 fn generate_armament_elements(counter_file: &std::fs::File, record: &Record) {
@@ -68,12 +80,18 @@ fn generate_armament_elements(counter_file: &std::fs::File, record: &Record) {
 		y_position -= generate_malfunction_elements(&counter_file, &record.sa_malfunction, y_position);
 
 		if !record.common.ma.rof.text.is_empty() {
-			y_position -= generate_rof_element(&counter_file, &record.common.ma.rof, y_position, &record.common.ma.color, record.common.overrides.ma.multiple_hits);
-		}	
+			y_position -= generate_rof_element(&counter_file, &record.common.ma.rof, y_position, &record.common.ma.color, record.common.overrides.ma.multiple_hits, record.markings.intensive_fire_in_gun_stack());
+		} else if record.markings.intensive_fire_in_gun_stack() {
+			y_position -= generate_rof_element(&counter_file, &record.markings.intensive_fire, y_position, &record.common.ma.color, false, false);
+		}
 
 		if !record.common.ma.ife.text.is_empty() && !record.common.ma.ife.alternate_location.is_empty() {
 			generate_ife_element_alternate_location(counter_file, &record.common.ma, y_position);
-		}		
+		}
+
+		generate_smoke_elements(counter_file, &record.smoke, true, y_position);
+	} else {
+		generate_smoke_elements(counter_file, &record.smoke, false, GUN_COLUMN_Y_POSITION);
 	}
 
 	if MOD_LOCATION_ABOVE_MGS == record.transport_values.pp.alternate_location {
@@ -93,11 +111,12 @@ fn generate_counter_front(counter_file: &std::fs::File, path: &String, record: &
 	generate_counter_background_svg(counter_file, 60, &record.common.colors, &record.common.overrides);
 	generate_debug_working_area_svg(&counter_file);
 	record.common.turret.generate_svg_elements(&counter_file);
-	generate_unit_depiction_svg(counter_file, &path, &record.common.piece_front, &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
+	generate_unit_depiction_svg(counter_file, &path, &record.common.overrides.depiction(&record.common.piece_front), &record.common.note, &record.common.svg_image_transform, true, &record.common.name, record.common.display_name, &record.common.colors, &record.args);
 	generate_armament_elements(&counter_file, &record); // Construct the whole "gun stack" of information, containing (potentially) the gun caliber, ROF, breakdown number, IFE, PP #, etc.).
 	record.armor.generate_svg_elements(&counter_file);
 	record.mgs.generate_svg_elements(&counter_file);
 	record.movement_values.generate_svg_elements(&counter_file, &record.common.colors);	
+	record.markings.generate_svg_elements(&counter_file, !record.common.overrides.ma.ignore);
 
 	if record.transport_values.manhandling_number.is_set {
 		generate_motorcycle_manhandling_number_element(&counter_file, &record.transport_values.manhandling_number, &record.common.colors.text);
@@ -215,7 +234,9 @@ fn generate_counter(record: &mut Record, note_number: &String) {
 		Ok(counter_file) => counter_file,
 	};
 
-	generate_counter_header_svg_elements("vasl_vehicle_counters", &counter_file, 60, &record.common.name, &note_number, &record.common.comments, &record.common.version);
+	add_manifest_entry(&path, &record.common.piece_front, &record.common.nationality, MANIFEST_FRONT, MANIFEST_GENERATED);
+
+	generate_counter_header_svg_elements("vasl_vehicle_counters", &counter_file, 60, &record.common.name, &note_number, &record.common.comments, &record.common.version, &record.common.metadata.for_piece(&record.common.piece_front));
 	generate_counter_front(&counter_file, &path, record);
 	generate_footer_svg(&counter_file);
 
@@ -243,16 +264,18 @@ TODO: NOT YET? */
 	}
 }
 
-fn generate_counters(record: &mut Record) {
+fn generate_counters(record: &mut Record) -> io::Result<()> {
 	let note_number: String = record.common.note.clone();
 	
 	if record.common.overrides.copy {
-		let _ = copy_counter("veh", &record.common.nationality, &record.common.piece_front, &note_number, &record.args);
+		copy_counter("veh", &record.common.nationality, &record.common.piece_front, &note_number, MANIFEST_FRONT, &record.args)?;
 	} else if !record.common.nationality.is_empty() {
 		generate_counter(record, &note_number);
 	} else {
 		println!("Missing nationality for piece '{0}'", record.common.piece_front);
 	}
+
+	Ok(())
 }
 //
 // We don't need to derive `Debug` (which doesn't require Serde), but it's a
@@ -312,6 +335,11 @@ impl SpreadsheetRecord {
 		
 		result.common.initialize(&nationality, &self.notes, &self.name, &self.ma, &"".to_string(), &self.rof_ife, &self.breakdown, &self.version, &self.piece, &self.svg_image_transform, &self.comments);
 		
+		result.common.metadata = CounterMetadata::new("vehicle", &nationality);
+		result.common.metadata.set_chapter_h_values(&self.notes, &self.bpv, &self.rf, &self.dates);
+		result.common.metadata.weight = strip_html_tags(&self.weight);
+		result.common.metadata.count = strip_html_tags(&self.count);
+		
 		result.common.turret = sanitize_mount(&self.gt, &result.common.overrides, &result.common.colors);
 		
 		result.common.ma.special_ammo.sanitize(&self.ammunition, &result.common.overrides.special_ammo, RANGE_FONTS, &result.common.colors);
@@ -331,6 +359,10 @@ impl SpreadsheetRecord {
 
 		result.mgs.sanitize(&self.bmg, &self.cmg, &self.aamg, &result.common.overrides, &result.common.colors);
 		
+		result.smoke.sanitize(&self.smoke_depletion, &self.smoke_discharger, &result.common.overrides, &result.common.colors);
+		
+		result.markings.sanitize(&self.intensive_fire, &self.radioless, &self.cs, &result.common.overrides, &result.common.colors);
+		
 		return result;
 	}
 }
@@ -339,11 +371,18 @@ fn run() -> Result<(), Box<dyn Error>> {
 	let mut args = Arguments::parse();
 	
 	args.sanitize_destination();
+	select_palette(&args.palette);
+	select_render_profile(&args.profile);
 	
+	let manifest: ManifestWriter = ManifestWriter::new("veh", &args);
 	let mut rdr = csv::Reader::from_reader(io::stdin());
+	let headers = rdr.headers()?.clone();
 
-	for result in rdr.deserialize() {
-		let mut spreadsheet_record: SpreadsheetRecord = result?;
+	for result in rdr.records() {
+		let row = result?;
+		let mut spreadsheet_record: SpreadsheetRecord = row.deserialize(Some(&headers))?;
+
+		set_manifest_source_row(&row);
 
 		if NOVR_ANNOUNCE == spreadsheet_record.overrides {
 			if !args.quiet {
@@ -354,21 +393,27 @@ fn run() -> Result<(), Box<dyn Error>> {
 			let pieces = record.common.pieces.clone();
 			
 			for piece in pieces {
-				if !piece.contains('@') {
+				if !piece.contains(NATIONALITY_DELIMITER) {
 					record.common.piece_front = piece.to_string();
-					generate_counters(&mut record);
+					generate_counters(&mut record)?;
 				} else {
-					let (piece_name, nationality) = piece.split_once("@").unwrap();
+					let (piece_name, nationality) = split_alternate_nationality(&piece);
 					let mut alt_record: Record = spreadsheet_record.sanitize(&nationality.to_string(), &args);
 					
 					alt_record.common.colors = nationality_to_colors(&nationality.to_string());
 					alt_record.common.piece_front = piece_name.to_string();
 					
-					generate_counters(&mut alt_record);
+					generate_counters(&mut alt_record)?;
 				}
 			}
 		}
 	}
+
+	inline_generated_counters(&args);
+	optimize_generated_counters(&args);
+	manifest.write();
+	check_missing_depictions(&args)?;
+
 	Ok(())
 }
 
@@ -0,0 +1,288 @@
+use std::{error::Error, fs, process};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::sync::OnceLock;
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use regex::Regex;
+use roxmltree::{Document, Node};
+use serde::Deserialize;
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::manifest::*;
+
+pub const ALLOW_LIST_FILE: &str =	"./data/vasl_svg_allow_list.csv";
+pub const ANY_ELEMENT: &str =		"*";
+pub const UNVERIFIED_TIER: &str =	"unverified";
+
+const SVG_NAMESPACE: &str =		"http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str =	"http://www.w3.org/1999/xlink";
+const XML_NAMESPACE: &str =		"http://www.w3.org/XML/1998/namespace";
+const XHTML_NAMESPACE: &str =	"http://www.w3.org/1999/xhtml";
+//
+// A url() that doesn't start with '#' refers to another file ("--shared-defs"'s "url(../../counter_defs.svg#highlight_filter)"),
+// which VASL has to follow but resvg and most browsers don't.
+//
+const EXTERNAL_REFERENCE: &str =	r#"url\(\s*['"]?[^#'"\s)][^)]*\)"#;
+
+static EXTERNAL_REFERENCES: OnceLock<Regex> = OnceLock::new();
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct LintArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[arg(long, default_value = "")]
+	allow_list: String,	// Defaults to ALLOW_LIST_FILE.
+}
+//
+// One row per element or attribute known to render in VASL:
+//
+//	element:	SVG element name, "*" for an attribute allowed on any element.
+//	attribute:	attribute or style property (style="fill:red" is checked like fill="red"), empty for the element itself.
+//	value:		regular expression the whole value has to match ("|" separated alternatives), any value when empty.
+//	tier:		empty when it's known to render, "unverified" when it's used but hasn't been checked in VASL yet.
+//	notes:		why it's there.
+//
+// Unverified elements and values are reported (once per kind, with the number of counters using them) but don't fail the run.
+//
+// Elements and attributes outside the SVG namespace are written "xlink:href", "xhtml:link" ... Attributes of other namespaces
+// (inkscape:, sodipodi: ...) are editor data that renderers ignore and aren't checked.
+//
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+struct AllowListEntry {
+	element: String,
+	attribute: String,
+	value: String,
+	#[serde(default)]
+	tier: String,
+	notes: String,
+}
+//
+// The unverified tier's notes, None when it's verified.
+//
+type Tier = Option<String>;
+//
+// The value's regular expression (None allows any value) and its tier.
+//
+type Rule = (Option<Regex>, Tier);
+
+#[derive(Default)]
+struct AllowList {
+	elements: BTreeMap<String, Tier>,
+	attributes: BTreeMap<(String, String), Vec<Rule>>,
+}
+
+enum Finding {
+	Violation(String),
+	Unverified(String),
+}
+
+impl AllowList {
+	fn read(path: &String) -> AllowList {
+		let mut result: AllowList = Default::default();
+		let file = match File::open(path) {
+			Err(why) => panic!("couldn't open file: {0} {1}", path, why),
+			Ok(file) => file,
+		};
+		let mut rdr = csv::Reader::from_reader(file);
+
+		for entry in rdr.deserialize() {
+			let entry: AllowListEntry = match entry {
+				Err(why) => panic!("AllowList::read()@{0}: bad entry in {1}: {2}", line!(), path, why),
+				Ok(entry) => entry,
+			};
+
+			let tier: Tier = match entry.tier.as_str() {
+				"" => None,
+				UNVERIFIED_TIER => Some(entry.notes.to_string()),
+				_ => panic!("AllowList::read()@{0}: bad tier '{1}' for {2} {3} in {4}", line!(), entry.tier, entry.element, entry.attribute, path),
+			};
+
+			if entry.attribute.is_empty() {
+				result.elements.insert(entry.element, tier);
+				continue;
+			}
+
+			let value: Option<Regex> = match entry.value.is_empty() {
+				true => None,
+				false => match Regex::new(&format!("^(?:{0})$", entry.value)) {
+					Err(why) => panic!("AllowList::read()@{0}: bad value '{1}' for {2} {3} in {4}: {5}", line!(), entry.value, entry.element, entry.attribute, path, why),
+					Ok(value) => Some(value),
+				},
+			};
+
+			result.attributes.entry((entry.element, entry.attribute)).or_default().push((value, tier));
+		}
+
+		return result;
+	}
+
+	fn check_attribute(&self, element: &String, attribute: &String, value: &str) -> Option<Finding> {
+		let mut rules: Vec<&Rule> = Default::default();
+
+		for key in [(element.to_string(), attribute.to_string()), (ANY_ELEMENT.to_string(), attribute.to_string())] {
+			if let Some(found) = self.attributes.get(&key) {
+				rules.extend(found.iter());
+			}
+		}
+
+		if rules.is_empty() {
+			return Some(Finding::Violation(format!("<{element}> attribute '{attribute}'")));
+		}
+
+		let matching: Vec<&Tier> = rules.iter().filter(|(rule, _)| rule.as_ref().is_none_or(|rule| rule.is_match(value.trim()))).map(|(_, tier)| tier).collect();
+
+		if matching.is_empty() {
+			return Some(Finding::Violation(format!("<{element}> {attribute}=\"{0}\"", value.trim())));
+		}
+
+		if matching.iter().any(|tier| tier.is_none()) {
+			return None;
+		}
+
+		return matching[0].as_ref().map(|notes| Finding::Unverified(format!("<{element}> {attribute} ({notes})")));
+	}
+}
+//
+// "xlink:href", "xhtml:link" ..., SVG names are left as they are and unknown namespaces are written out.
+//
+fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+	return match namespace {
+		None | Some(SVG_NAMESPACE) => name.to_string(),
+		Some(XLINK_NAMESPACE) => format!("xlink:{name}"),
+		Some(XML_NAMESPACE) => format!("xml:{name}"),
+		Some(XHTML_NAMESPACE) => format!("xhtml:{name}"),
+		Some(namespace) => format!("{{{namespace}}}{name}"),
+	};
+}
+
+fn lint_element(node: &Node, allow_list: &AllowList, violations: &mut BTreeMap<String, usize>, unverified: &mut BTreeSet<String>) {
+	let element: String = qualified_name(node.tag_name().namespace(), node.tag_name().name());
+	let external_references: &Regex = EXTERNAL_REFERENCES.get_or_init(|| Regex::new(EXTERNAL_REFERENCE).unwrap());
+
+	match allow_list.elements.get(&element) {
+		None => {
+			*violations.entry(format!("<{element}>")).or_default() += 1;
+			return;
+		}
+		Some(Some(notes)) => { unverified.insert(format!("<{element}> ({notes})")); },
+		Some(None) => {},
+	}
+
+	for attribute in node.attributes() {
+		if !matches!(attribute.namespace(), None | Some(XLINK_NAMESPACE) | Some(XML_NAMESPACE)) {
+			continue;
+		}
+
+		let name: String = qualified_name(attribute.namespace(), attribute.name());
+		let mut checks: Vec<(String, String)> = vec![(name.to_string(), attribute.value().to_string())];
+
+		if "style" == name {
+			for declaration in attribute.value().split(';') {
+				if let Some((property, value)) = declaration.split_once(':') {
+					checks.push((property.trim().to_string(), value.to_string()));
+				}
+			}
+		}
+
+		for (attribute, value) in checks {
+			for found in external_references.find_iter(&value).filter(|_| "style" != attribute) {
+				*violations.entry(format!("<{element}> {attribute} refers to another file: {0}", found.as_str())).or_default() += 1;
+			}
+
+			match allow_list.check_attribute(&element, &attribute, &value) {
+				Some(Finding::Violation(violation)) => *violations.entry(violation).or_default() += 1,
+				Some(Finding::Unverified(finding)) => { unverified.insert(finding); },
+				None => {},
+			}
+		}
+	}
+}
+//
+// Each violation once per counter, with the number of elements it was found on, and the unverified SVG it uses.
+//
+fn lint_counter(path: &String, allow_list: &AllowList) -> (BTreeMap<String, usize>, BTreeSet<String>) {
+	let mut result: (BTreeMap<String, usize>, BTreeSet<String>) = Default::default();
+	let text: String = match fs::read_to_string(path) {
+		Err(why) => {
+			result.0.insert(format!("couldn't read: {why}"), 1);
+			return result;
+		}
+		Ok(text) => text,
+	};
+	let document: Document = match Document::parse(&text) {
+		Err(why) => {
+			result.0.insert(format!("not well-formed XML: {why}"), 1);
+			return result;
+		}
+		Ok(document) => document,
+	};
+
+	for node in document.descendants().filter(|node| node.is_element()) {
+		lint_element(&node, allow_list, &mut result.0, &mut result.1);
+	}
+
+	return result;
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = LintArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let allow_list_file: String = if args.allow_list.is_empty() { ALLOW_LIST_FILE.to_string() } else { args.allow_list.to_string() };
+	let allow_list: AllowList = AllowList::read(&allow_list_file);
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let mut unverified: BTreeMap<String, usize> = Default::default();
+	let mut counters: usize = 0;
+
+	for entry in &entries {
+		let (violations, found): (BTreeMap<String, usize>, BTreeSet<String>) = lint_counter(&format!("{0}{1}", args.outputs.destination, entry.output), &allow_list);
+
+		for finding in found {
+			*unverified.entry(finding).or_default() += 1;
+		}
+
+		if violations.is_empty() {
+			continue;
+		}
+
+		counters += 1;
+
+		for (violation, count) in &violations {
+			if 1 < *count {
+				println!("{0} ({1}): {violation} ({count} elements)", entry.output, entry.piece);
+			} else {
+				println!("{0} ({1}): {violation}", entry.output, entry.piece);
+			}
+		}
+	}
+
+	for (finding, count) in &unverified {
+		println!("Unverified: {finding} ({count} counter(s))");
+	}
+
+	if !args.outputs.quiet {
+		println!("{0} counter(s) checked, {counters} with SVG not in the allow list or references to other files", entries.len());
+	}
+
+	if 0 != counters {
+		return Err(format!("{counters} counter(s) use SVG not known to work in VASL (see {allow_list_file}) or refer to other files").into());
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
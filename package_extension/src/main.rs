@@ -0,0 +1,213 @@
+use std::{error::Error, fs, process};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use regex::Regex;
+use zip::{CompressionMethod, ZipWriter};
+use zip::write::SimpleFileOptions;
+//
+// Local files.
+//
+use common_functions::VERSION;
+use common_functions::arguments::*;
+use common_functions::build_file::*;
+use common_functions::cached::*;
+use common_functions::manifest::*;
+
+pub const EXTENSION_FILE_NAME: &str =	"vasl_svg_counters";	// Written to the current folder as "<name>-<VERSION>.vmext" unless "--extension" says otherwise.
+
+const EXTENSION_ID: &str =			"svg";			// The gpids of the extension's pieces are "svg:<n>" (see PieceIds).
+const EXTENSION_TITLE: &str =		"SVG counters";	// The extension's counters window.
+const EXTENSION_MODULE: &str =		"VASL";
+const VASSAL_VERSION: &str =		"3.7.14";		// The one VASL 6.6.8 (and its review saves) were made with.
+const BUILD_FILE_ENTRY: &str =		"buildFile.xml";
+const METADATA_ENTRY: &str =		"extensiondata";	// VASSAL's moduledata, as it's named in an extension.
+const IMAGES_ENTRY: &str =			"images/";
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct PackageArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+
+	#[arg(long, default_value = "")]
+	extension: String,	// The .vmext to write, defaults to "<EXTENSION_FILE_NAME>-<VERSION>.vmext".
+}
+//
+// "ge/veh/../counter_defs.svg" is "ge/counter_defs.svg", None when it's outside the images folder.
+//
+fn normalize_path(path: &String) -> Option<String> {
+	let mut result: Vec<&str> = Default::default();
+
+	for part in path.split('/') {
+		match part {
+			"" | "." => {},
+			".." => { result.pop()?; },
+			_ => result.push(part),
+		}
+	}
+
+	return Some(result.join("/"));
+}
+//
+// The files a counter refers to (depictions, "--shared-defs"'s counter_defs.svg ...), relative to the images folder.
+//
+fn counter_references(args: &PackageArguments, output: &String) -> BTreeSet<String> {
+	let mut result: BTreeSet<String> = Default::default();
+	let text: String = fs::read_to_string(format!("{0}{output}", args.outputs.destination)).unwrap_or_default();
+	let directory: String = output.rsplit_once('/').map(|(directory, _)| format!("{directory}/")).unwrap_or_default();
+	let reference = Regex::new(r##"href\s*=\s*"([^"#]+)"|url\(\s*'([^'#]+)'|url\(\s*"([^"#]+)"|url\(\s*([^"'()#\s]+)\s*\)"##).unwrap();
+
+	for found in reference.captures_iter(&text) {
+		let href: String = found.iter().skip(1).flatten().next().map(|href| href.as_str()).unwrap_or_default().trim().replace("%20", " ");
+
+		if href.starts_with("data:") || href.contains("://") {
+			continue;
+		}
+
+		if let Some(path) = normalize_path(&format!("{directory}{href}")) {
+			result.insert(path);
+		}
+	}
+
+	return result;
+}
+//
+// The images folder's copy of the file, or the cached one when it hasn't been copied (see copy_cached_files).
+//
+fn image_source(args: &PackageArguments, image: &String) -> Option<String> {
+	return [ format!("{0}{image}", args.outputs.destination), format!("{CACHED_DIRECTORY}{image}") ].into_iter().find(|path| Path::new(path).is_file());
+}
+//
+// The pieces are added to a counters window of their own, one tab per nationality and category.
+//
+fn extension_build_file(args: &PackageArguments, groups: &Vec<(String, Vec<(&ManifestEntry, Option<&ManifestEntry>)>)>) -> String {
+	let mut ids: PieceIds = PieceIds { prefix: EXTENSION_ID.to_string(), used: Default::default() };
+	let mut widgets: String = Default::default();
+	let mut result: String = Default::default();
+
+	for (title, counters) in groups {
+		widgets.push_str(&list_widget(&args.outputs.destination, title, counters, &mut ids, 4));
+	}
+
+	result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+	result.push_str(&format!("<VASSAL.build.module.ModuleExtension anyModule=\"false\" description=\"{EXTENSION_TITLE} {VERSION}\" extensionId=\"{EXTENSION_ID}\" module=\"{EXTENSION_MODULE}\" moduleVersion=\"\" nextPieceSlotId=\"{0}\" vassalVersion=\"{VASSAL_VERSION}\" version=\"{VERSION}\">\n", ids.next_free()));
+	result.push_str("\t<VASSAL.build.module.ExtensionElement target=\"\">\n");
+	result.push_str(&format!("\t\t<VASSAL.build.module.PieceWindow allowMultiple=\"false\" buttonText=\"{EXTENSION_TITLE}\" hidden=\"false\" hotkey=\"\" icon=\"\" name=\"{EXTENSION_TITLE}\" scale=\"1.0\" tooltip=\"{EXTENSION_TITLE}\">\n"));
+	result.push_str(&format!("\t\t\t<VASSAL.build.widget.TabWidget entryName=\"{EXTENSION_TITLE}\">\n"));
+	result.push_str(&widgets);
+	result.push_str("\t\t\t</VASSAL.build.widget.TabWidget>\n");
+	result.push_str("\t\t</VASSAL.build.module.PieceWindow>\n");
+	result.push_str("\t</VASSAL.build.module.ExtensionElement>\n");
+	result.push_str("</VASSAL.build.module.ModuleExtension>\n");
+
+	return result;
+}
+
+fn extension_metadata() -> String {
+	let saved: u128 = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_millis()).unwrap_or(0);
+	let mut result: String = Default::default();
+
+	result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+	result.push_str("<data version=\"1\">\n");
+	result.push_str(&format!("  <version>{VERSION}</version>\n"));
+	result.push_str("  <extra1/>\n");
+	result.push_str("  <extra2/>\n");
+	result.push_str(&format!("  <VassalVersion>{VASSAL_VERSION}</VassalVersion>\n"));
+	result.push_str(&format!("  <dateSaved>{saved}</dateSaved>\n"));
+	result.push_str(&format!("  <description>{EXTENSION_TITLE} {VERSION}</description>\n"));
+	result.push_str("  <universal>false</universal>\n");
+	result.push_str(&format!("  <module>\n    <name>{EXTENSION_MODULE}</name>\n    <version/>\n  </module>\n"));
+	result.push_str("</data>\n");
+
+	return result;
+}
+
+fn write_extension(path: &String, build_file: &String, images: &BTreeMap<String, String>) -> Result<(), Box<dyn Error>> {
+	let mut zip = ZipWriter::new(File::create(path).map_err(|why| format!("couldn't create '{path}': {why}"))?);
+	let options: SimpleFileOptions = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+	zip.start_file(BUILD_FILE_ENTRY, options)?;
+	zip.write_all(build_file.as_bytes())?;
+	zip.start_file(METADATA_ENTRY, options)?;
+	zip.write_all(extension_metadata().as_bytes())?;
+
+	for (image, source) in images {
+		zip.start_file(format!("{IMAGES_ENTRY}{image}"), options)?;
+		zip.write_all(&fs::read(source).map_err(|why| format!("couldn't read '{source}': {why}"))?)?;
+	}
+
+	zip.finish()?;
+
+	return Ok(());
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = PackageArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let path: String = if args.extension.is_empty() { format!("./{EXTENSION_FILE_NAME}-{VERSION}.vmext") } else { args.extension.to_string() };
+	let selected: BTreeMap<(String, String), Vec<&ManifestEntry>> = group_manifest_entries(&entries, &args.outputs, &args.selection)?;
+	let mut groups: Vec<(String, Vec<(&ManifestEntry, Option<&ManifestEntry>)>)> = Default::default();
+	let mut images: BTreeMap<String, String> = Default::default();
+	let mut missing: Vec<String> = Default::default();
+	//
+	// The counters (generated or copied) have to be there, the files they refer to are only reported (as some data refers to
+	// depictions that don't exist yet).
+	//
+	for ((nationality, category), entries) in &selected {
+		for entry in entries {
+			let counter: String = format!("{0}{1}", args.outputs.destination, entry.output);
+
+			if !Path::new(&counter).is_file() {
+				missing.push(counter);
+				continue;
+			}
+
+			images.insert(entry.output.to_string(), counter);
+
+			for reference in counter_references(&args, &entry.output) {
+				match image_source(&args, &reference) {
+					Some(source) => { images.insert(reference, source); },
+					None => println!("{0}: '{reference}' not found, left out", entry.output),
+				}
+			}
+		}
+
+		groups.push((format!("{nationality} {category}"), pair_fronts_and_backs(entries)));
+	}
+
+	if !missing.is_empty() {
+		for counter in &missing {
+			println!("Missing: {counter}");
+		}
+
+		return Err(format!("{0} counter(s) of '{1}' not found, run run_all.sh first", missing.len(), manifest_file(&args.outputs.destination, &args.outputs.manifest)).into());
+	}
+
+	if !args.outputs.quiet {
+		println!("Writing {path} ({0} counters, {1} images)", groups.iter().map(|(_, counters)| counters.len()).sum::<usize>(), images.len());
+	}
+
+	write_extension(&path, &extension_build_file(&args, &groups), &images)?;
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}
@@ -2,9 +2,10 @@
 
 export BASH_ARGS=
 export DESTINATION="./images"
+export GENERATOR_ARGS=
 export NATIONALITY="allied american axis british chinese communist finnish french german italian japanese russian swedish un"
 
-while getopts dD:N:q option
+while getopts dD:iI:M:N:OP:qR:S option
 do
 	case "${option}" in
 		d)
@@ -13,18 +14,54 @@ do
 		D)
 			DESTINATION=${OPTARG}
 			;;
+		i)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -i"
+			;;
+		I)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -I ${OPTARG}"
+			;;
+		M)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -M ${OPTARG}"
+			;;
 		N)
 			NATIONALITY="${OPTARG}"
+			;;
+		O)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -O"
+			;;
+		P)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -P ${OPTARG}"
 			;;			
 		q)
 			BASH_ARGS="${BASH_ARGS} -q"
 			;;
+		R)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -R ${OPTARG}"
+			;;
+		S)
+			GENERATOR_ARGS="${GENERATOR_ARGS} -S"
+			;;
 	esac
 done
 
 BASH_ARGS="-D ${DESTINATION} ${BASH_ARGS}"
 RUST_ARGS="--destination ${DESTINATION} ${RUST_ARGS}"
 
-bash run_nationality.sh ${BASH_ARGS} -N "${NATIONALITY}"
+# Each generator adds its counters to the manifest, start with a fresh one.
+rm -f "${DESTINATION}/manifest.csv"
+
+# The scripts add the CSV files whose generator failed (e.g., "-M strict" with missing depictions) to FAILURES_FILE.
+export FAILURES_FILE=$(mktemp)
+
+bash run_nationality.sh ${BASH_ARGS} ${GENERATOR_ARGS} -N "${NATIONALITY}"
+
+bash run_miscellaneous.sh ${BASH_ARGS} ${GENERATOR_ARGS}
+
+FAILED=$(cat "${FAILURES_FILE}" | xargs)
+rm -f "${FAILURES_FILE}"
 
-bash run_miscellaneous.sh ${BASH_ARGS}
+if [ -n "${FAILED}" ]
+then
+	echo "Failed: ${FAILED}"
+	exit 1
+fi
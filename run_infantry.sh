@@ -1,55 +1,88 @@
 #!/bin/bash
 
-export BASH_ARGS=
 export RUST_ARGS=
+export COPY_ARGS=
 export DESTINATION="./images"
+export FAILED=
 export NATIONALITY="allied american axis british chinese communist finnish french german italian japanese russian swedish un"
 
-while getopts dD:N:q option
+while getopts dD:iI:M:N:OP:qR:S option
 do
 	case "${option}" in
 		d)
-			BASH_ARGS="${BASH_ARGS} -d"
 			RUST_ARGS="${RUST_ARGS} --debug"
 			;;
 		D)
 			DESTINATION="${OPTARG}"
 			;;
+		i)
+			RUST_ARGS="${RUST_ARGS} --inline"
+			;;
+		I)
+			RUST_ARGS="${RUST_ARGS} --images ${OPTARG}"
+			;;
+		M)
+			RUST_ARGS="${RUST_ARGS} --missing ${OPTARG}"
+			;;
 		N)
 			NATIONALITY="${OPTARG}"
 			;;
+		O)
+			RUST_ARGS="${RUST_ARGS} --optimize"
+			;;
+		P)
+			RUST_ARGS="${RUST_ARGS} --palette ${OPTARG}"
+			;;
 		q)
-			BASH_ARGS="${BASH_ARGS} -q"
 			RUST_ARGS="${RUST_ARGS} --quiet"
+			COPY_ARGS="${COPY_ARGS} --quiet"
+			;;
+		R)
+			RUST_ARGS="${RUST_ARGS} --profile ${OPTARG}"
+			;;
+		S)
+			RUST_ARGS="${RUST_ARGS} --shared-defs"
 			;;
 	esac
 done
 
-BASH_ARGS="-D ${DESTINATION} ${BASH_ARGS}"
 RUST_ARGS="--destination ${DESTINATION} ${RUST_ARGS}"
+COPY_ARGS="--destination ${DESTINATION} ${COPY_ARGS}"
 
 for n in ${NATIONALITY}
 do
-	bash run_copy_cached_files.sh ${BASH_ARGS} -N ${n} -C inf
+	cargo run --bin copy_cached_files -- ${COPY_ARGS} --nationality ${n} --category inf || FAILED="${FAILED} copy_cached_files:${n}"
 
 	CSV_FILE=data/${n}_smc.csv
 	if [ -f "${CSV_FILE}" ]
 	then
 		echo "Generating ${n} single man counters"
-		cargo run --bin generate_singleman_counters -- ${RUST_ARGS} < ${CSV_FILE}
+		cargo run --bin generate_singleman_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 	fi
 
 	CSV_FILE=data/${n}_mmc.csv
 	if [ -f "${CSV_FILE}" ]
 	then
 		echo "Generating ${n} multi-man counters"
-		cargo run --bin generate_multiman_counters -- ${RUST_ARGS} < ${CSV_FILE}
+		cargo run --bin generate_multiman_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 	fi
 	
 	CSV_FILE=data/${n}_sw.csv
 	if [ -f "${CSV_FILE}" ]
 	then
 		echo "Generating ${n} support weapon counters"
-		cargo run --bin generate_sw_counters -- ${RUST_ARGS} < ${CSV_FILE}
+		cargo run --bin generate_sw_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 	fi	
 done
+
+if [ -n "${FAILED}" ]
+then
+	echo "Failed:${FAILED}"
+
+	if [ -n "${FAILURES_FILE}" ]
+	then
+		echo "${FAILED}" >> "${FAILURES_FILE}"
+	fi
+
+	exit 1
+fi
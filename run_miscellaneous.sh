@@ -1,9 +1,11 @@
 #!/bin/bash
 
 export RUST_ARGS=
+export COPY_ARGS=
 export DESTINATION="./images"
+export FAILED=
 
-while getopts dD:q option
+while getopts dD:iI:M:OP:qR:S option
 do
 	case "${option}" in
 		d)
@@ -12,56 +14,76 @@ do
 		D)
 			DESTINATION="${OPTARG}"
 			;;
+		i)
+			RUST_ARGS="${RUST_ARGS} --inline"
+			;;
+		I)
+			RUST_ARGS="${RUST_ARGS} --images ${OPTARG}"
+			;;
+		M)
+			RUST_ARGS="${RUST_ARGS} --missing ${OPTARG}"
+			;;
+		O)
+			RUST_ARGS="${RUST_ARGS} --optimize"
+			;;
+		P)
+			RUST_ARGS="${RUST_ARGS} --palette ${OPTARG}"
+			;;
 		q)
 			RUST_ARGS="${RUST_ARGS} --quiet"
+			COPY_ARGS="${COPY_ARGS} --quiet"
+			;;
+		R)
+			RUST_ARGS="${RUST_ARGS} --profile ${OPTARG}"
+			;;
+		S)
+			RUST_ARGS="${RUST_ARGS} --shared-defs"
 			;;
 	esac
 done
 
 RUST_ARGS="--destination ${DESTINATION} ${RUST_ARGS}"
+COPY_ARGS="--destination ${DESTINATION} ${COPY_ARGS}"
 
-if [ -d "cached/sh" ]; then
-	if [ -d "cached/sh/svg" ] ; then
-		cp -r cached/sh/svg/* ${DESTINATION}/sh/svg
-	fi	
-fi
-
-if [ -d "cached/ML" ]; then
-	if [ -d "cached/ML/svg" ] ; then
-		cp -r cached/ML/svg/* ${DESTINATION}/ML/svg
-	fi	
-fi
-
-if [ -d "cached/MS" ]; then
-	if [ -d "cached/MS/svg" ] ; then
-		cp -r cached/MS/svg/* ${DESTINATION}/MS/svg
-	fi	
-fi
+# Nationalities without a group (shared and miscellaneous counters), see data/nationalities.csv.
+cargo run --bin copy_cached_files -- ${COPY_ARGS} --nationality "sh ML MS" --category all || FAILED="${FAILED} copy_cached_files:sh/ML/MS"
 
 CSV_FILE=data/landing_craft_and_boats.csv
 if [ -f "${CSV_FILE}" ]
 then
 	echo "Generating landing craft and boats"
-	cargo run --bin generate_landing_craft_counters -- ${RUST_ARGS} < ${CSV_FILE}
+	cargo run --bin generate_landing_craft_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 fi
 
 CSV_FILE=data/common_vehicles.csv
 if [ -f "${CSV_FILE}" ]
 then
 	echo "Generating shared vehicles"
-	cargo run --bin generate_vehicle_counters -- ${RUST_ARGS} < ${CSV_FILE}
+	cargo run --bin generate_vehicle_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 fi
 
 CSV_FILE=data/aircraft.csv
 if [ -f "${CSV_FILE}" ]
 then
 	echo "Generating aircraft"
-	cargo run --bin generate_aircraft_counters -- ${RUST_ARGS} < ${CSV_FILE}
+	cargo run --bin generate_aircraft_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
 fi
 
 CSV_FILE=data/miscellaneous.csv
 if [ -f "${CSV_FILE}" ]
 then
 	echo "Generating miscellaneous counters"
-	cargo run --bin generate_miscellaneous_counters -- ${RUST_ARGS} < ${CSV_FILE}
+	cargo run --bin generate_miscellaneous_counters -- ${RUST_ARGS} --source ${CSV_FILE} < ${CSV_FILE} || FAILED="${FAILED} ${CSV_FILE}"
+fi
+
+if [ -n "${FAILED}" ]
+then
+	echo "Failed:${FAILED}"
+
+	if [ -n "${FAILURES_FILE}" ]
+	then
+		echo "${FAILED}" >> "${FAILURES_FILE}"
+	fi
+
+	exit 1
 fi
@@ -0,0 +1,110 @@
+use std::{error::Error, fs, process};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+//
+// Command line argument processing.
+//
+use clap::Parser;
+use resvg::{tiny_skia, usvg};
+//
+// Local files.
+//
+use common_functions::arguments::*;
+use common_functions::manifest::*;
+//
+// Draws every counter in the manifest (at 100% zoom, nothing is written) and reports how long parsing and drawing took, e.g., to
+// compare render profiles: generate into two destinations, one with "--profile flat", and time both. resvg isn't VASL's renderer,
+// the ratio between the two runs is what counts.
+//
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct TimingArguments {
+	#[command(flatten)]
+	outputs: OutputArguments,
+
+	#[command(flatten)]
+	selection: SelectionArguments,
+}
+
+#[derive(Default)]
+struct Timings {
+	counters: usize,
+	failed: Vec<String>,
+	parsing: Duration,
+	drawing: Duration,
+}
+
+fn time_counters(args: &TimingArguments) -> Timings {
+	let mut result: Timings = Default::default();
+	let entries: Vec<ManifestEntry> = required_manifest(&args.outputs);
+	let mut fonts = usvg::fontdb::Database::new();
+
+	fonts.load_system_fonts();
+
+	let fonts: Arc<usvg::fontdb::Database> = Arc::new(fonts);
+
+	for entry in select_manifest_entries(&entries, &args.selection) {
+		let path: String = format!("{0}{1}", args.outputs.destination, entry.output);
+		let Ok(data) = fs::read(&path) else {
+			result.failed.push(format!("{0}: couldn't read", entry.output));
+			continue;
+		};
+		let options: usvg::Options = usvg::Options {
+			resources_dir: Path::new(&path).parent().map(|directory| directory.to_path_buf()),
+			fontdb: fonts.clone(),
+			..Default::default()
+		};
+		let start: Instant = Instant::now();
+		let tree: usvg::Tree = match usvg::Tree::from_data(&data, &options) {
+			Err(why) => {
+				result.failed.push(format!("{0}: {why}", entry.output));
+				continue;
+			}
+			Ok(tree) => tree,
+		};
+
+		result.parsing += start.elapsed();
+
+		let size = tree.size().to_int_size();
+		let Some(mut pixmap) = tiny_skia::Pixmap::new(size.width(), size.height()) else {
+			result.failed.push(format!("{0}: empty counter", entry.output));
+			continue;
+		};
+		let start: Instant = Instant::now();
+
+		resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+		result.drawing += start.elapsed();
+		result.counters += 1;
+	}
+
+	return result;
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+	let mut args = TimingArguments::parse();
+
+	args.outputs.sanitize_destination();
+
+	let timings: Timings = time_counters(&args);
+
+	for failed in &timings.failed {
+		println!("Failed: {failed}");
+	}
+
+	if 0 == timings.counters {
+		return Err("No counters drawn".into());
+	}
+
+	println!("{0} counter(s): parsing {1:.0} ms, drawing {2:.0} ms ({3:.3} ms per counter)", timings.counters, timings.parsing.as_secs_f64() * 1000.0, timings.drawing.as_secs_f64() * 1000.0, timings.drawing.as_secs_f64() * 1000.0 / timings.counters as f64);
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(err) = run() {
+		println!("{}", err);
+		process::exit(1);
+	}
+}